@@ -0,0 +1,15 @@
+// Compiles the wire format into OUT_DIR. Generated structs and the descriptor set are used
+// by the tests only - to check the hand written models in src/protobuf_models against the proto file.
+fn main() {
+    let proto = "proto/my_service_bus_persistence.proto";
+    println!("cargo:rerun-if-changed={}", proto);
+
+    let out_dir = std::path::PathBuf::from(std::env::var("OUT_DIR").unwrap());
+
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path().unwrap());
+
+    prost_build::Config::new()
+        .file_descriptor_set_path(out_dir.join("my_service_bus_persistence.bin"))
+        .compile_protos(&[proto], &["proto"])
+        .unwrap();
+}
@@ -0,0 +1,448 @@
+use std::io::Write;
+
+use my_service_bus_shared::{
+    page_compressor::{CompressedPageBuilder, CompressedPageFile},
+    page_export,
+    page_id::PageId,
+    protobuf_models::MessageProtobufModel,
+};
+
+const USAGE: &str = "Usage: msb-page <command> <args>
+
+Commands:
+    ls <page-file>                                  List messages of the page
+    cat <page-file> <message-id>                    Print headers and payload of the message
+    verify <page-file>                              Check that every message decodes and belongs to the page
+    stats <page-file>                               Print compression statistics
+    convert <page-file> <dest-file> [--by-files]    Rewrite the page as a single file (default) or by files
+    export --json|--csv <page-file>                 Print messages as JSON Lines or CSV
+    import --json|--csv <src-file> <dest-file>      Build a page file from JSON Lines or CSV";
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    let args: Vec<&str> = args.iter().map(|itm| itm.as_str()).collect();
+
+    if let Err(err) = run(args.as_slice(), &mut std::io::stdout().lock()) {
+        eprintln!("{}", err);
+        std::process::exit(1);
+    }
+}
+
+fn run(args: &[&str], out: &mut impl Write) -> Result<(), String> {
+    match args {
+        ["ls", file_name] => ls(file_name, out),
+        ["cat", file_name, message_id] => cat(file_name, message_id, out),
+        ["verify", file_name] => verify(file_name, out),
+        ["stats", file_name] => stats(file_name, out),
+        ["convert", file_name, dest_file_name] => convert(file_name, dest_file_name, false, out),
+        ["convert", file_name, dest_file_name, "--by-files"] => {
+            convert(file_name, dest_file_name, true, out)
+        }
+        ["export", "--json", file_name] => export(file_name, false, out),
+        ["export", "--csv", file_name] => export(file_name, true, out),
+        ["import", "--json", file_name, dest_file_name] => {
+            import(file_name, dest_file_name, false, out)
+        }
+        ["import", "--csv", file_name, dest_file_name] => {
+            import(file_name, dest_file_name, true, out)
+        }
+        _ => Err(USAGE.to_string()),
+    }
+}
+
+fn write_error(err: std::io::Error) -> String {
+    format!("Can not write output. Err: {}", err)
+}
+
+fn open_page_file(file_name: &str) -> Result<CompressedPageFile, String> {
+    CompressedPageFile::open(file_name)
+        .map_err(|err| format!("Can not open page file {}. Err: {:?}", file_name, err))
+}
+
+fn read_messages(
+    file_name: &str,
+    mut callback: impl FnMut(MessageProtobufModel) -> Result<(), String>,
+) -> Result<(), String> {
+    let file = open_page_file(file_name)?;
+
+    let mut reader = file
+        .get_reader()
+        .map_err(|err| format!("Can not read page file {}. Err: {:?}", file_name, err))?;
+
+    loop {
+        let message = reader
+            .get_next_message()
+            .map_err(|err| format!("Can not decode message. Err: {:?}", err))?;
+
+        match message {
+            Some(message) => callback(message)?,
+            None => return Ok(()),
+        }
+    }
+}
+
+fn ls(file_name: &str, out: &mut impl Write) -> Result<(), String> {
+    read_messages(file_name, |message| {
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{}",
+            message.get_message_id().get_value(),
+            page_export::format_rfc3339(message.get_created()),
+            message.data.len(),
+            message.headers.len()
+        )
+        .map_err(write_error)
+    })
+}
+
+fn cat(file_name: &str, message_id: &str, out: &mut impl Write) -> Result<(), String> {
+    let message_id: i64 = message_id
+        .parse()
+        .map_err(|_| format!("Invalid message id: {}", message_id))?;
+
+    let file = open_page_file(file_name)?;
+
+    let mut reader = file
+        .get_reader()
+        .map_err(|err| format!("Can not read page file {}. Err: {:?}", file_name, err))?;
+
+    let message = reader
+        .get_message(message_id.into())
+        .map_err(|err| format!("Can not decode message. Err: {:?}", err))?
+        .ok_or_else(|| format!("Message {} is not found", message_id))?;
+
+    for header in &message.headers {
+        writeln!(out, "{}: {}", header.key, header.value).map_err(write_error)?;
+    }
+
+    writeln!(out).map_err(write_error)?;
+    out.write_all(message.data.as_slice())
+        .map_err(write_error)?;
+
+    Ok(())
+}
+
+fn verify(file_name: &str, out: &mut impl Write) -> Result<(), String> {
+    let mut page_id: Option<PageId> = None;
+    let mut prev_message_id: Option<i64> = None;
+    let mut messages_amount = 0;
+    let mut errors = Vec::new();
+
+    read_messages(file_name, |message| {
+        let message_id = message.get_message_id();
+
+        let message_page_id = PageId::from_message_id(message_id);
+
+        match page_id {
+            Some(page_id) => {
+                if page_id.get_value() != message_page_id.get_value() {
+                    errors.push(format!(
+                        "Message {} belongs to page {} but page is {}",
+                        message_id.get_value(),
+                        message_page_id,
+                        page_id
+                    ));
+                }
+            }
+            None => page_id = Some(message_page_id),
+        }
+
+        if let Some(prev_message_id) = prev_message_id {
+            if prev_message_id >= message_id.get_value() {
+                errors.push(format!(
+                    "Message {} goes after message {}",
+                    message_id.get_value(),
+                    prev_message_id
+                ));
+            }
+        }
+
+        prev_message_id = Some(message_id.get_value());
+        messages_amount += 1;
+
+        Ok(())
+    })?;
+
+    for err in &errors {
+        writeln!(out, "{}", err).map_err(write_error)?;
+    }
+
+    match page_id {
+        Some(page_id) => writeln!(out, "Page: {}. Messages: {}", page_id, messages_amount),
+        None => writeln!(out, "Page is empty"),
+    }
+    .map_err(write_error)?;
+
+    if !errors.is_empty() {
+        return Err(format!("Found {} problems", errors.len()));
+    }
+
+    writeln!(out, "OK").map_err(write_error)?;
+
+    Ok(())
+}
+
+fn stats(file_name: &str, out: &mut impl Write) -> Result<(), String> {
+    let file = open_page_file(file_name)?;
+
+    let stats = file
+        .get_compression_stats(10)
+        .map_err(|err| format!("Can not read page file {}. Err: {:?}", file_name, err))?;
+
+    writeln!(out, "Messages: {}", stats.messages_amount).map_err(write_error)?;
+    writeln!(out, "Compressed size: {}", stats.compressed_size).map_err(write_error)?;
+    writeln!(out, "Uncompressed size: {}", stats.uncompressed_size).map_err(write_error)?;
+    writeln!(out, "Ratio: {:.3}", stats.get_ratio()).map_err(write_error)?;
+
+    writeln!(out).map_err(write_error)?;
+    writeln!(out, "Entries:").map_err(write_error)?;
+    for entry in &stats.entries {
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{}\t{:.3}",
+            entry.name,
+            entry.compression_method,
+            entry.compressed_size,
+            entry.uncompressed_size,
+            entry.get_ratio()
+        )
+        .map_err(write_error)?;
+    }
+
+    writeln!(out).map_err(write_error)?;
+    writeln!(out, "Largest messages:").map_err(write_error)?;
+    for message in &stats.largest_messages {
+        writeln!(out, "{}\t{}", message.message_id, message.size).map_err(write_error)?;
+    }
+
+    Ok(())
+}
+
+fn convert(
+    file_name: &str,
+    dest_file_name: &str,
+    by_files: bool,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let mut builder = if by_files {
+        CompressedPageBuilder::new_by_files()
+    } else {
+        CompressedPageBuilder::new_as_single_file()
+    };
+
+    read_messages(file_name, |message| {
+        builder
+            .add_message(&message)
+            .map_err(|err| format!("Can not add message. Err: {:?}", err))
+    })?;
+
+    let payload = builder
+        .get_payload()
+        .map_err(|err| format!("Can not compress page. Err: {:?}", err))?;
+
+    std::fs::write(dest_file_name, payload)
+        .map_err(|err| format!("Can not write file {}. Err: {}", dest_file_name, err))?;
+
+    writeln!(
+        out,
+        "Converted {} messages into {}",
+        builder.messages_count(),
+        dest_file_name
+    )
+    .map_err(write_error)?;
+
+    Ok(())
+}
+
+fn export(file_name: &str, as_csv: bool, out: &mut impl Write) -> Result<(), String> {
+    let file = open_page_file(file_name)?;
+
+    let mut reader = file
+        .get_reader()
+        .map_err(|err| format!("Can not read page file {}. Err: {:?}", file_name, err))?;
+
+    let result = if as_csv {
+        page_export::export_as_csv(&mut reader, out)
+    } else {
+        page_export::export_as_json_lines(&mut reader, out)
+    };
+
+    result.map_err(|err| format!("Can not export page. Err: {:?}", err))?;
+
+    Ok(())
+}
+
+fn import(
+    file_name: &str,
+    dest_file_name: &str,
+    as_csv: bool,
+    out: &mut impl Write,
+) -> Result<(), String> {
+    let src = std::fs::File::open(file_name)
+        .map_err(|err| format!("Can not open file {}. Err: {}", file_name, err))?;
+
+    let mut builder = CompressedPageBuilder::new_as_single_file();
+
+    let result = if as_csv {
+        page_export::import_from_csv(std::io::BufReader::new(src), &mut builder)
+    } else {
+        page_export::import_from_json_lines(std::io::BufReader::new(src), &mut builder)
+    };
+
+    let amount = result.map_err(|err| format!("Can not import page. Err: {:?}", err))?;
+
+    let payload = builder
+        .get_payload()
+        .map_err(|err| format!("Can not compress page. Err: {:?}", err))?;
+
+    std::fs::write(dest_file_name, payload)
+        .map_err(|err| format!("Can not write file {}. Err: {}", dest_file_name, err))?;
+
+    writeln!(out, "Imported {} messages into {}", amount, dest_file_name).map_err(write_error)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::*;
+
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new(name: &str) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("msb-page-{}-{}", name, std::process::id()));
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+
+        fn get_file_name(&self, name: &str) -> String {
+            self.0.join(name).to_str().unwrap().to_string()
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn write_page(file_name: &str, message_ids: &[i64], by_files: bool) {
+        let mut builder = if by_files {
+            CompressedPageBuilder::new_by_files()
+        } else {
+            CompressedPageBuilder::new_as_single_file()
+        };
+
+        for message_id in message_ids {
+            let message = MessageProtobufModel::new(
+                (*message_id).into(),
+                DateTimeAsMicroseconds::new(1_000_000),
+                format!("message-{}", message_id).into_bytes(),
+                vec![],
+            );
+
+            builder.add_message(&message).unwrap();
+        }
+
+        std::fs::write(file_name, builder.get_payload().unwrap()).unwrap();
+    }
+
+    fn read_page(file_name: &str) -> Vec<MessageProtobufModel> {
+        let mut result = Vec::new();
+
+        read_messages(file_name, |message| {
+            result.push(message);
+            Ok(())
+        })
+        .unwrap();
+
+        result
+    }
+
+    fn run_command(args: &[&str]) -> (Result<(), String>, String) {
+        let mut out = Vec::new();
+        let result = run(args, &mut out);
+        (result, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_verify() {
+        let dir = TempDir::new("verify");
+        let file_name = dir.get_file_name("page");
+
+        write_page(&file_name, &[100_000, 100_001, 100_005], false);
+
+        let (result, out) = run_command(&["verify", &file_name]);
+
+        assert_eq!(Ok(()), result);
+        assert_eq!("Page: 1. Messages: 3\nOK\n", out);
+    }
+
+    #[test]
+    fn test_verify_reports_problems() {
+        let dir = TempDir::new("verify-problems");
+        let file_name = dir.get_file_name("page");
+
+        write_page(&file_name, &[100_001, 100_000, 200_000], true);
+
+        let (result, out) = run_command(&["verify", &file_name]);
+
+        assert_eq!(Err("Found 2 problems".to_string()), result);
+        assert_eq!(
+            "Message 100000 goes after message 100001\n\
+             Message 200000 belongs to page 2 but page is 1\n\
+             Page: 1. Messages: 3\n",
+            out
+        );
+
+        let (result, _) = run_command(&["verify", &dir.get_file_name("not-found")]);
+        assert!(result.unwrap_err().starts_with("Can not open page file"));
+    }
+
+    #[test]
+    fn test_convert() {
+        let dir = TempDir::new("convert");
+        let file_name = dir.get_file_name("page");
+        let single_file_name = dir.get_file_name("single");
+        let by_files_name = dir.get_file_name("by-files");
+
+        write_page(&file_name, &[5, 6, 7], true);
+
+        let (result, out) = run_command(&["convert", &file_name, &single_file_name]);
+
+        assert_eq!(Ok(()), result);
+        assert_eq!(
+            format!("Converted 3 messages into {}\n", single_file_name),
+            out
+        );
+
+        let file = CompressedPageFile::open(&single_file_name).unwrap();
+        assert_eq!(1, file.get_reader().unwrap().get_files_amount());
+
+        let (result, _) =
+            run_command(&["convert", &single_file_name, &by_files_name, "--by-files"]);
+        assert_eq!(Ok(()), result);
+
+        let file = CompressedPageFile::open(&by_files_name).unwrap();
+        assert_eq!(3, file.get_reader().unwrap().get_files_amount());
+
+        let src = read_page(&file_name);
+        assert_eq!(src, read_page(&single_file_name));
+        assert_eq!(src, read_page(&by_files_name));
+    }
+
+    #[test]
+    fn test_unknown_command_prints_usage() {
+        let (result, out) = run_command(&["convert", "page"]);
+
+        assert_eq!(Err(USAGE.to_string()), result);
+        assert!(out.is_empty());
+    }
+}
@@ -3,6 +3,7 @@ use std::collections::{HashMap, VecDeque};
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LockItem {
     pub id: i64,
     pub data: VecDeque<String>,
@@ -83,3 +84,21 @@ impl Locks {
         result
     }
 }
+
+#[cfg(all(test, feature = "serde"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serde_of_lock_items() {
+        let mut locks = Locks::new();
+        locks.new_lock(1, "process".to_string());
+
+        let json = serde_json::to_string(&locks.get_all()).unwrap();
+
+        assert!(json.starts_with("[{\"id\":1,\"data\":[\"process\"],\"date\":"));
+
+        let result: Vec<LockItem> = serde_json::from_str(json.as_str()).unwrap();
+        assert_eq!("process", result[0].to_string());
+    }
+}
@@ -1,7 +1,12 @@
 //TODO - Restore or delete
 //pub mod messages_page;
+pub mod messages_stream;
 pub mod page_compressor;
+pub mod page_export;
 pub mod page_id;
+pub mod page_presence;
+pub mod page_storage_path;
+pub mod payload_validation;
 pub mod protobuf_models;
 
 pub mod settings;
@@ -9,3 +14,6 @@ pub mod sub_page;
 
 pub mod debug;
 pub mod validators;
+
+#[cfg(feature = "serde")]
+mod serde_base64;
@@ -0,0 +1,34 @@
+use prost::DecodeError;
+
+// Offsets point to the first byte of the frame which failed.
+#[derive(Debug)]
+pub enum MessagesStreamError {
+    IoError(std::io::Error),
+    TruncatedLength {
+        offset: u64,
+        read: usize,
+    },
+    TruncatedMessage {
+        offset: u64,
+        expected: usize,
+        read: usize,
+    },
+    InvalidLength {
+        offset: u64,
+    },
+    MessageIsTooBig {
+        offset: u64,
+        size: u64,
+        max_size: usize,
+    },
+    DecodeError {
+        offset: u64,
+        err: DecodeError,
+    },
+}
+
+impl From<std::io::Error> for MessagesStreamError {
+    fn from(src: std::io::Error) -> Self {
+        Self::IoError(src)
+    }
+}
@@ -0,0 +1,80 @@
+use crate::protobuf_models::MessageProtobufModel;
+
+use super::MessagesStreamError;
+
+const MAX_VARINT_LEN: usize = 10;
+
+// Frame parsing shared by the sync and async readers - they only feed it with the bytes they read.
+pub struct FrameReader {
+    offset: u64,
+    max_message_size: usize,
+    length: u64,
+    length_len: usize,
+    size: usize,
+}
+
+impl FrameReader {
+    pub fn new(offset: u64, max_message_size: usize) -> Self {
+        Self {
+            offset,
+            max_message_size,
+            length: 0,
+            length_len: 0,
+            size: 0,
+        }
+    }
+
+    // Returns the payload size once the last byte of the length prefix is pushed
+    pub fn push_length_byte(&mut self, byte: u8) -> Result<Option<usize>, MessagesStreamError> {
+        if self.length_len == MAX_VARINT_LEN {
+            return Err(MessagesStreamError::InvalidLength {
+                offset: self.offset,
+            });
+        }
+
+        self.length |= ((byte & 0x7f) as u64) << (self.length_len * 7);
+        self.length_len += 1;
+
+        if byte & 0x80 != 0 {
+            return Ok(None);
+        }
+
+        if self.length > self.max_message_size as u64 {
+            return Err(MessagesStreamError::MessageIsTooBig {
+                offset: self.offset,
+                size: self.length,
+                max_size: self.max_message_size,
+            });
+        }
+
+        self.size = self.length as usize;
+        Ok(Some(self.size))
+    }
+
+    // Stream is allowed to end only at the frame boundary
+    pub fn end_of_stream_in_length(&self) -> Result<(), MessagesStreamError> {
+        if self.length_len == 0 {
+            return Ok(());
+        }
+
+        Err(MessagesStreamError::TruncatedLength {
+            offset: self.offset,
+            read: self.length_len,
+        })
+    }
+
+    pub fn end_of_stream_in_payload(&self, read: usize) -> MessagesStreamError {
+        MessagesStreamError::TruncatedMessage {
+            offset: self.offset,
+            expected: self.size,
+            read,
+        }
+    }
+
+    pub fn decode(&self, payload: &[u8]) -> Result<MessageProtobufModel, MessagesStreamError> {
+        MessageProtobufModel::parse(payload).map_err(|err| MessagesStreamError::DecodeError {
+            offset: self.offset,
+            err,
+        })
+    }
+}
@@ -0,0 +1,157 @@
+use tokio::io::{AsyncRead, AsyncReadExt};
+
+use crate::protobuf_models::MessageProtobufModel;
+
+use super::{frame_reader::FrameReader, MessagesStreamError, DEFAULT_MAX_MESSAGE_SIZE};
+
+// Reads byte by byte while parsing frame length, so wrap unbuffered sources into BufReader.
+pub struct MessagesStreamAsyncReader<R: AsyncRead + Unpin> {
+    reader: R,
+    offset: u64,
+    max_message_size: usize,
+}
+
+impl<R: AsyncRead + Unpin> MessagesStreamAsyncReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            offset: 0,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+
+    pub fn get_offset(&self) -> u64 {
+        self.offset
+    }
+
+    // Returns None if the stream ends exactly at the frame boundary
+    pub async fn read_message(
+        &mut self,
+    ) -> Result<Option<MessageProtobufModel>, MessagesStreamError> {
+        let mut frame = FrameReader::new(self.offset, self.max_message_size);
+        let mut byte = [0u8];
+
+        let size = loop {
+            if self.read(&mut byte).await? == 0 {
+                frame.end_of_stream_in_length()?;
+                return Ok(None);
+            }
+
+            if let Some(size) = frame.push_length_byte(byte[0])? {
+                break size;
+            }
+        };
+
+        let mut payload = vec![0u8; size];
+        let mut read = 0;
+
+        while read < size {
+            let read_size = self.read(&mut payload[read..]).await?;
+
+            if read_size == 0 {
+                return Err(frame.end_of_stream_in_payload(read));
+            }
+
+            read += read_size;
+        }
+
+        frame.decode(payload.as_slice()).map(Some)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    async fn read(&mut self, buf: &mut [u8]) -> Result<usize, MessagesStreamError> {
+        let size = self.reader.read(buf).await?;
+        self.offset += size as u64;
+        Ok(size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::messages_stream::MessagesStreamAsyncWriter;
+
+    use super::*;
+
+    fn create_message(id: i64) -> MessageProtobufModel {
+        MessageProtobufModel::new(
+            id.into(),
+            DateTimeAsMicroseconds::new(id),
+            vec![id as u8; 100],
+            vec![],
+        )
+    }
+
+    #[tokio::test]
+    async fn test_write_and_read() {
+        let mut writer = MessagesStreamAsyncWriter::new(Vec::new());
+
+        writer.write_message(&create_message(1)).await.unwrap();
+        writer.write_message(&create_message(2)).await.unwrap();
+
+        let payload = writer.into_inner();
+
+        let mut reader = MessagesStreamAsyncReader::new(payload.as_slice());
+
+        assert_eq!(
+            create_message(1),
+            reader.read_message().await.unwrap().unwrap()
+        );
+        assert_eq!(
+            create_message(2),
+            reader.read_message().await.unwrap().unwrap()
+        );
+        assert!(reader.read_message().await.unwrap().is_none());
+        assert_eq!(payload.len() as u64, reader.get_offset());
+    }
+
+    #[tokio::test]
+    async fn test_truncated_message() {
+        let mut writer = MessagesStreamAsyncWriter::new(Vec::new());
+        writer.write_message(&create_message(1)).await.unwrap();
+        let payload = writer.into_inner();
+
+        let mut reader = MessagesStreamAsyncReader::new(&payload[..payload.len() - 1]);
+
+        match reader.read_message().await {
+            Err(MessagesStreamError::TruncatedMessage {
+                offset,
+                expected,
+                read,
+            }) => {
+                assert_eq!(0, offset);
+                assert_eq!(expected - 1, read);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_truncated_length() {
+        let mut writer = MessagesStreamAsyncWriter::new(Vec::new());
+        writer.write_message(&create_message(1)).await.unwrap();
+        let mut payload = writer.into_inner();
+
+        let frame_len = payload.len();
+
+        // The first byte of a length prefix which has the continuation bit set
+        payload.push(0x80);
+
+        let mut reader = MessagesStreamAsyncReader::new(payload.as_slice());
+
+        reader.read_message().await.unwrap().unwrap();
+
+        assert!(matches!(
+            reader.read_message().await,
+            Err(MessagesStreamError::TruncatedLength { offset, read: 1 }) if offset == frame_len as u64
+        ));
+    }
+}
@@ -0,0 +1,189 @@
+use std::io::Read;
+
+use crate::protobuf_models::MessageProtobufModel;
+
+use super::{frame_reader::FrameReader, MessagesStreamError, DEFAULT_MAX_MESSAGE_SIZE};
+
+// Reads byte by byte while parsing frame length, so wrap unbuffered sources into BufReader.
+pub struct MessagesStreamReader<R: Read> {
+    reader: R,
+    offset: u64,
+    max_message_size: usize,
+}
+
+impl<R: Read> MessagesStreamReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            offset: 0,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+
+    pub fn set_max_message_size(&mut self, max_message_size: usize) {
+        self.max_message_size = max_message_size;
+    }
+
+    pub fn get_offset(&self) -> u64 {
+        self.offset
+    }
+
+    // Returns None if the stream ends exactly at the frame boundary
+    pub fn read_message(&mut self) -> Result<Option<MessageProtobufModel>, MessagesStreamError> {
+        let mut frame = FrameReader::new(self.offset, self.max_message_size);
+        let mut byte = [0u8];
+
+        let size = loop {
+            if self.read(&mut byte)? == 0 {
+                frame.end_of_stream_in_length()?;
+                return Ok(None);
+            }
+
+            if let Some(size) = frame.push_length_byte(byte[0])? {
+                break size;
+            }
+        };
+
+        let mut payload = vec![0u8; size];
+        let mut read = 0;
+
+        while read < size {
+            let read_size = self.read(&mut payload[read..])?;
+
+            if read_size == 0 {
+                return Err(frame.end_of_stream_in_payload(read));
+            }
+
+            read += read_size;
+        }
+
+        frame.decode(payload.as_slice()).map(Some)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, MessagesStreamError> {
+        loop {
+            match self.reader.read(buf) {
+                Ok(size) => {
+                    self.offset += size as u64;
+                    return Ok(size);
+                }
+                Err(err) if err.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err.into()),
+            }
+        }
+    }
+}
+
+impl<R: Read> Iterator for MessagesStreamReader<R> {
+    type Item = Result<MessageProtobufModel, MessagesStreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_message().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::messages_stream::MessagesStreamWriter;
+
+    use super::*;
+
+    fn create_message(id: i64, size: usize) -> MessageProtobufModel {
+        MessageProtobufModel::new(
+            id.into(),
+            DateTimeAsMicroseconds::new(id),
+            vec![id as u8; size],
+            vec![],
+        )
+    }
+
+    fn write_messages(messages: &[MessageProtobufModel]) -> Vec<u8> {
+        let mut writer = MessagesStreamWriter::new(Vec::new());
+
+        for message in messages {
+            writer.write_message(message).unwrap();
+        }
+
+        let written_bytes = writer.get_written_bytes();
+        let result = writer.into_inner();
+
+        assert_eq!(written_bytes, result.len() as u64);
+
+        result
+    }
+
+    #[test]
+    fn test_write_and_read() {
+        let messages = vec![create_message(1, 10), create_message(2, 200)];
+
+        let payload = write_messages(&messages);
+
+        let result: Vec<MessageProtobufModel> = MessagesStreamReader::new(payload.as_slice())
+            .map(|itm| itm.unwrap())
+            .collect();
+
+        assert_eq!(messages, result);
+    }
+
+    #[test]
+    fn test_empty_stream() {
+        let mut reader = MessagesStreamReader::new([0u8; 0].as_slice());
+        assert!(reader.read_message().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_truncated_message() {
+        let messages = vec![create_message(1, 10), create_message(2, 200)];
+
+        let payload = write_messages(&messages);
+
+        let first_frame_len = write_messages(&messages[..1]).len();
+
+        let mut reader = MessagesStreamReader::new(&payload[..payload.len() - 5]);
+
+        reader.read_message().unwrap().unwrap();
+
+        match reader.read_message() {
+            Err(MessagesStreamError::TruncatedMessage {
+                offset,
+                expected,
+                read,
+            }) => {
+                assert_eq!(first_frame_len as u64, offset);
+                assert_eq!(expected - 5, read);
+            }
+            other => panic!("Unexpected result: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_truncated_length() {
+        let payload = write_messages(&[create_message(1, 200)]);
+
+        let mut reader = MessagesStreamReader::new(&payload[..1]);
+
+        assert!(matches!(
+            reader.read_message(),
+            Err(MessagesStreamError::TruncatedLength { offset: 0, read: 1 })
+        ));
+    }
+
+    #[test]
+    fn test_message_is_too_big() {
+        let payload = write_messages(&[create_message(1, 200)]);
+
+        let mut reader = MessagesStreamReader::new(payload.as_slice());
+        reader.set_max_message_size(100);
+
+        assert!(matches!(
+            reader.read_message(),
+            Err(MessagesStreamError::MessageIsTooBig { offset: 0, .. })
+        ));
+    }
+}
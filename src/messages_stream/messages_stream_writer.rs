@@ -0,0 +1,41 @@
+use std::io::Write;
+
+use crate::protobuf_models::MessageProtobufModel;
+
+pub struct MessagesStreamWriter<W: Write> {
+    writer: W,
+    buffer: Vec<u8>,
+    written: u64,
+}
+
+impl<W: Write> MessagesStreamWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buffer: Vec::new(),
+            written: 0,
+        }
+    }
+
+    pub fn write_message(&mut self, message: &MessageProtobufModel) -> std::io::Result<()> {
+        self.buffer.clear();
+        prost::Message::encode_length_delimited(message, &mut self.buffer).unwrap();
+
+        self.writer.write_all(self.buffer.as_slice())?;
+        self.written += self.buffer.len() as u64;
+
+        Ok(())
+    }
+
+    pub fn get_written_bytes(&self) -> u64 {
+        self.written
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
@@ -0,0 +1,13 @@
+mod error;
+mod frame_reader;
+mod messages_stream_async_reader;
+mod messages_stream_async_writer;
+mod messages_stream_reader;
+mod messages_stream_writer;
+pub use error::*;
+pub use messages_stream_async_reader::*;
+pub use messages_stream_async_writer::*;
+pub use messages_stream_reader::*;
+pub use messages_stream_writer::*;
+
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 64 * 1024 * 1024;
@@ -1,7 +1,8 @@
 use crate::protobuf_models::MessageProtobufModel;
 
 use super::{
-    CompressedPageBuilderByFiles, CompressedPageBuilderSingleFile, CompressedPageWriterError,
+    CompressedPageBuilderByFiles, CompressedPageBuilderLimits, CompressedPageBuilderSingleFile,
+    CompressedPageBuilderSize, CompressedPageWriterError,
 };
 
 pub enum CompressedPageBuilder {
@@ -18,19 +19,53 @@ impl CompressedPageBuilder {
         Self::ByFiles(CompressedPageBuilderByFiles::new())
     }
 
+    pub fn new_as_single_file_with_limits(limits: CompressedPageBuilderLimits) -> Self {
+        Self::SingleFile(CompressedPageBuilderSingleFile::new_with_limits(limits))
+    }
+
+    pub fn new_by_files_with_limits(limits: CompressedPageBuilderLimits) -> Self {
+        Self::ByFiles(CompressedPageBuilderByFiles::new_with_limits(limits))
+    }
+
+    // Returns PageFull and keeps the message out of the page if it would exceed one of the limits.
+    // The very first message is always accepted so an oversized message still gets its own page.
     pub fn add_message(
         &mut self,
         model: &MessageProtobufModel,
     ) -> Result<(), CompressedPageWriterError> {
         match self {
-            CompressedPageBuilder::SingleFile(single_file) => {
-                single_file.add_message(model);
-                Ok(())
-            }
+            CompressedPageBuilder::SingleFile(single_file) => single_file.add_message(model),
             CompressedPageBuilder::ByFiles(by_files) => by_files.add_message(model),
         }
     }
 
+    pub fn messages_count(&self) -> usize {
+        self.get_size().messages_count
+    }
+
+    pub fn uncompressed_bytes(&self) -> usize {
+        self.get_size().uncompressed_bytes
+    }
+
+    pub fn estimated_compressed_size(&self) -> usize {
+        self.get_size()
+            .estimated_compressed_size(self.get_limits().compression_ratio_estimate)
+    }
+
+    pub fn get_size(&self) -> &CompressedPageBuilderSize {
+        match self {
+            CompressedPageBuilder::SingleFile(single_file) => single_file.get_size(),
+            CompressedPageBuilder::ByFiles(by_files) => by_files.get_size(),
+        }
+    }
+
+    pub fn get_limits(&self) -> &CompressedPageBuilderLimits {
+        match self {
+            CompressedPageBuilder::SingleFile(single_file) => single_file.get_limits(),
+            CompressedPageBuilder::ByFiles(by_files) => by_files.get_limits(),
+        }
+    }
+
     pub fn get_payload(&mut self) -> Result<Vec<u8>, CompressedPageWriterError> {
         match self {
             CompressedPageBuilder::SingleFile(single_file) => single_file.get_payload(),
@@ -38,3 +73,92 @@ impl CompressedPageBuilder {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::page_compressor::CompressedPageReader;
+
+    use super::*;
+
+    fn create_message(id: i64) -> MessageProtobufModel {
+        MessageProtobufModel::new(
+            id.into(),
+            DateTimeAsMicroseconds::now(),
+            vec![id as u8; 100],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_size_accounting() {
+        let mut builder = CompressedPageBuilder::new_as_single_file();
+
+        assert_eq!(0, builder.messages_count());
+        assert_eq!(0, builder.uncompressed_bytes());
+
+        let msg = create_message(1);
+        builder.add_message(&msg).unwrap();
+        builder.add_message(&create_message(2)).unwrap();
+
+        assert_eq!(2, builder.messages_count());
+        assert!(builder.uncompressed_bytes() > 2 * msg.data.len());
+
+        let uncompressed_bytes = builder.uncompressed_bytes();
+        let estimated_compressed_size = builder.estimated_compressed_size();
+
+        let payload = builder.get_payload().unwrap();
+
+        assert!(estimated_compressed_size >= payload.len());
+        assert!(estimated_compressed_size > uncompressed_bytes);
+    }
+
+    #[test]
+    fn test_page_full_by_messages_count() {
+        let mut limits = CompressedPageBuilderLimits::unlimited();
+        limits.max_messages_count = Some(2);
+
+        let mut builder = CompressedPageBuilder::new_by_files_with_limits(limits);
+
+        builder.add_message(&create_message(1)).unwrap();
+        builder.add_message(&create_message(2)).unwrap();
+
+        let result = builder.add_message(&create_message(3));
+
+        assert!(matches!(result, Err(CompressedPageWriterError::PageFull)));
+        assert_eq!(2, builder.messages_count());
+
+        let payload = builder.get_payload().unwrap();
+        let reader = CompressedPageReader::new(payload).unwrap();
+
+        assert_eq!(2, reader.get_messages_amount());
+    }
+
+    #[test]
+    fn test_page_full_by_compressed_size() {
+        let mut limits = CompressedPageBuilderLimits::unlimited();
+        limits.max_compressed_bytes = Some(300);
+
+        let mut builder = CompressedPageBuilder::new_as_single_file_with_limits(limits);
+
+        builder.add_message(&create_message(1)).unwrap();
+
+        let result = builder.add_message(&create_message(2));
+
+        assert!(matches!(result, Err(CompressedPageWriterError::PageFull)));
+        assert_eq!(1, builder.messages_count());
+    }
+
+    #[test]
+    fn test_first_message_is_accepted_even_if_it_is_too_big() {
+        let mut limits = CompressedPageBuilderLimits::unlimited();
+        limits.max_uncompressed_bytes = Some(10);
+
+        let mut builder = CompressedPageBuilder::new_as_single_file_with_limits(limits);
+
+        builder.add_message(&create_message(1)).unwrap();
+
+        assert_eq!(1, builder.messages_count());
+    }
+}
@@ -2,19 +2,27 @@ use std::io::Write;
 
 use crate::{page_compressor::vec_writer::VecWriter, protobuf_models::MessageProtobufModel};
 
-use super::CompressedPageWriterError;
+use super::{CompressedPageBuilderLimits, CompressedPageBuilderSize, CompressedPageWriterError};
 
 pub struct CompressedPageBuilderByFiles {
     zip_writer: zip::ZipWriter<VecWriter>,
     options: zip::write::FileOptions,
+    size: CompressedPageBuilderSize,
+    limits: CompressedPageBuilderLimits,
 }
 
 impl CompressedPageBuilderByFiles {
     pub fn new() -> Self {
+        Self::new_with_limits(CompressedPageBuilderLimits::unlimited())
+    }
+
+    pub fn new_with_limits(limits: CompressedPageBuilderLimits) -> Self {
         let result = Self {
             zip_writer: zip::ZipWriter::new(VecWriter::new()),
             options: zip::write::FileOptions::default()
                 .compression_method(zip::CompressionMethod::Deflated),
+            size: CompressedPageBuilderSize::new_by_files(),
+            limits,
         };
 
         result
@@ -27,6 +35,14 @@ impl CompressedPageBuilderByFiles {
         let message_id = model.get_message_id();
         let file_name = format!("{}", message_id.get_value());
 
+        let new_size = self
+            .size
+            .add_file_message(file_name.len(), prost::Message::encoded_len(model));
+
+        if self.size.messages_count > 0 && self.limits.is_exceeded(&new_size) {
+            return Err(CompressedPageWriterError::PageFull);
+        }
+
         let mut payload = Vec::new();
 
         model.serialize(&mut payload).unwrap();
@@ -43,9 +59,19 @@ impl CompressedPageBuilderByFiles {
             pos += size;
         }
 
+        self.size = new_size;
+
         Ok(())
     }
 
+    pub fn get_size(&self) -> &CompressedPageBuilderSize {
+        &self.size
+    }
+
+    pub fn get_limits(&self) -> &CompressedPageBuilderLimits {
+        &self.limits
+    }
+
     pub fn get_payload(&mut self) -> Result<Vec<u8>, CompressedPageWriterError> {
         let result = self.zip_writer.finish()?;
         Ok(result.buf)
@@ -5,23 +5,53 @@ use crate::{
     protobuf_models::{MessageProtobufModel, MessagesProtobufModel},
 };
 
-use super::CompressedPageWriterError;
+use super::{CompressedPageBuilderLimits, CompressedPageBuilderSize, CompressedPageWriterError};
 
 pub struct CompressedPageBuilderSingleFile {
     messages: Option<Vec<MessageProtobufModel>>,
+    size: CompressedPageBuilderSize,
+    limits: CompressedPageBuilderLimits,
 }
 
 impl CompressedPageBuilderSingleFile {
     pub fn new() -> Self {
+        Self::new_with_limits(CompressedPageBuilderLimits::unlimited())
+    }
+
+    pub fn new_with_limits(limits: CompressedPageBuilderLimits) -> Self {
         let result = Self {
             messages: Some(Vec::new()),
+            size: CompressedPageBuilderSize::new_as_single_file(),
+            limits,
         };
 
         result
     }
 
-    pub fn add_message(&mut self, model: &MessageProtobufModel) {
+    pub fn add_message(
+        &mut self,
+        model: &MessageProtobufModel,
+    ) -> Result<(), CompressedPageWriterError> {
+        let new_size = self
+            .size
+            .add_single_file_message(prost::Message::encoded_len(model));
+
+        if self.size.messages_count > 0 && self.limits.is_exceeded(&new_size) {
+            return Err(CompressedPageWriterError::PageFull);
+        }
+
         self.messages.as_mut().unwrap().push(model.clone());
+        self.size = new_size;
+
+        Ok(())
+    }
+
+    pub fn get_size(&self) -> &CompressedPageBuilderSize {
+        &self.size
+    }
+
+    pub fn get_limits(&self) -> &CompressedPageBuilderLimits {
+        &self.limits
     }
 
     pub fn get_payload(&mut self) -> Result<Vec<u8>, CompressedPageWriterError> {
@@ -77,7 +107,7 @@ mod tests {
             vec![],
         );
 
-        builder.add_message(&msg1);
+        builder.add_message(&msg1).unwrap();
 
         let msg2 = MessageProtobufModel::new(
             2.into(),
@@ -86,7 +116,7 @@ mod tests {
             vec![],
         );
 
-        builder.add_message(&msg2);
+        builder.add_message(&msg2).unwrap();
 
         let compressed = builder.get_payload().unwrap();
 
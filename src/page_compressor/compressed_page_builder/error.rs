@@ -6,6 +6,7 @@ pub enum CompressedPageWriterError {
     ProtobufEncodeError(EncodeError),
     ZipError(ZipError),
     IoError(std::io::Error),
+    PageFull,
 }
 
 impl From<EncodeError> for CompressedPageWriterError {
@@ -0,0 +1,187 @@
+const ZIP_END_OF_CENTRAL_DIRECTORY_SIZE: usize = 22;
+const ZIP_LOCAL_FILE_HEADER_SIZE: usize = 30;
+const ZIP_CENTRAL_DIRECTORY_HEADER_SIZE: usize = 46;
+const ZIP_DATA_DESCRIPTOR_SIZE: usize = 24;
+const ZIP64_LOCAL_EXTRA_FIELD_SIZE: usize = 20;
+const ZIP64_CENTRAL_EXTRA_FIELD_SIZE: usize = 28;
+const ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE: usize = 56 + 20;
+
+// Per entry overhead we may get in the worst case: data descriptor and zip64 extra fields
+const ZIP_ENTRY_SIZE: usize = ZIP_LOCAL_FILE_HEADER_SIZE
+    + ZIP_CENTRAL_DIRECTORY_HEADER_SIZE
+    + ZIP_DATA_DESCRIPTOR_SIZE
+    + ZIP64_LOCAL_EXTRA_FIELD_SIZE
+    + ZIP64_CENTRAL_EXTRA_FIELD_SIZE;
+
+const DEFLATE_STORED_BLOCK_SIZE: usize = 65_535;
+const DEFLATE_STORED_BLOCK_HEADER_SIZE: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct CompressedPageBuilderLimits {
+    pub max_messages_count: Option<usize>,
+    pub max_uncompressed_bytes: Option<usize>,
+    pub max_compressed_bytes: Option<usize>,
+    // Deflate falls back to stored blocks on incompressible data, and their headers are counted
+    // as zip overhead, so with 1.0 estimated size is an upper bound.
+    // Topics with well compressible payloads can lower it to get bigger pages.
+    pub compression_ratio_estimate: f64,
+}
+
+impl CompressedPageBuilderLimits {
+    pub fn unlimited() -> Self {
+        Self {
+            max_messages_count: None,
+            max_uncompressed_bytes: None,
+            max_compressed_bytes: None,
+            compression_ratio_estimate: 1.0,
+        }
+    }
+
+    pub fn is_exceeded(&self, size: &CompressedPageBuilderSize) -> bool {
+        if let Some(max_messages_count) = self.max_messages_count {
+            if size.messages_count > max_messages_count {
+                return true;
+            }
+        }
+
+        if let Some(max_uncompressed_bytes) = self.max_uncompressed_bytes {
+            if size.uncompressed_bytes > max_uncompressed_bytes {
+                return true;
+            }
+        }
+
+        if let Some(max_compressed_bytes) = self.max_compressed_bytes {
+            if size.estimated_compressed_size(self.compression_ratio_estimate)
+                > max_compressed_bytes
+            {
+                return true;
+            }
+        }
+
+        false
+    }
+}
+
+impl Default for CompressedPageBuilderLimits {
+    fn default() -> Self {
+        Self::unlimited()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CompressedPageBuilderSize {
+    pub messages_count: usize,
+    pub uncompressed_bytes: usize,
+    zip_overhead_bytes: usize,
+    single_file: bool,
+}
+
+impl CompressedPageBuilderSize {
+    pub fn new_as_single_file() -> Self {
+        Self {
+            messages_count: 0,
+            uncompressed_bytes: 0,
+            zip_overhead_bytes: ZIP_END_OF_CENTRAL_DIRECTORY_SIZE
+                + ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE
+                + ZIP_ENTRY_SIZE
+                + 2,
+            single_file: true,
+        }
+    }
+
+    pub fn new_by_files() -> Self {
+        Self {
+            messages_count: 0,
+            uncompressed_bytes: 0,
+            zip_overhead_bytes: ZIP_END_OF_CENTRAL_DIRECTORY_SIZE
+                + ZIP64_END_OF_CENTRAL_DIRECTORY_SIZE,
+            single_file: false,
+        }
+    }
+
+    pub fn add_single_file_message(&self, encoded_len: usize) -> Self {
+        Self {
+            messages_count: self.messages_count + 1,
+            uncompressed_bytes: self.uncompressed_bytes
+                + prost::length_delimiter_len(encoded_len)
+                + 1
+                + encoded_len,
+            zip_overhead_bytes: self.zip_overhead_bytes,
+            single_file: self.single_file,
+        }
+    }
+
+    pub fn add_file_message(&self, file_name_len: usize, encoded_len: usize) -> Self {
+        Self {
+            messages_count: self.messages_count + 1,
+            uncompressed_bytes: self.uncompressed_bytes + encoded_len,
+            zip_overhead_bytes: self.zip_overhead_bytes
+                + ZIP_ENTRY_SIZE
+                + file_name_len * 2
+                + get_stored_blocks_overhead(encoded_len),
+            single_file: self.single_file,
+        }
+    }
+
+    pub fn estimated_compressed_size(&self, compression_ratio: f64) -> usize {
+        let mut result = (self.uncompressed_bytes as f64 * compression_ratio).ceil() as usize
+            + self.zip_overhead_bytes;
+
+        // By files builder counts stored blocks of every file when message is added
+        if self.single_file {
+            result += get_stored_blocks_overhead(self.uncompressed_bytes);
+        }
+
+        result
+    }
+}
+
+fn get_stored_blocks_overhead(len: usize) -> usize {
+    len.div_ceil(DEFLATE_STORED_BLOCK_SIZE).max(1) * DEFLATE_STORED_BLOCK_HEADER_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    // Random bytes are not compressible, so deflate stores them as is
+    fn create_incompressible_payload(len: usize) -> Vec<u8> {
+        let mut seed: u64 = 0x2545f4914f6cdd1d;
+
+        (0..len)
+            .map(|_| {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_estimation_is_upper_bound_for_incompressible_payload() {
+        let payload = create_incompressible_payload(300_000);
+
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        let options = zip::write::FileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated)
+            .large_file(true);
+        zip.start_file("d", options).unwrap();
+        zip.write_all(&payload).unwrap();
+        let zipped = zip.finish().unwrap().into_inner();
+
+        let mut size = CompressedPageBuilderSize::new_by_files();
+        size = size.add_file_message(1, payload.len());
+
+        assert!(size.estimated_compressed_size(1.0) >= zipped.len());
+    }
+
+    #[test]
+    fn test_stored_blocks_overhead() {
+        assert_eq!(5, get_stored_blocks_overhead(0));
+        assert_eq!(5, get_stored_blocks_overhead(65_535));
+        assert_eq!(10, get_stored_blocks_overhead(65_536));
+    }
+}
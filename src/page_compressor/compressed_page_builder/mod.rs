@@ -2,7 +2,9 @@ mod compressed_page_builder;
 mod compressed_page_builder_by_files;
 mod compressed_page_builder_single_file;
 mod error;
+mod limits;
 pub use compressed_page_builder::*;
 pub use compressed_page_builder_by_files::*;
 pub use compressed_page_builder_single_file::*;
 pub use error::*;
+pub use limits::*;
@@ -0,0 +1,109 @@
+use std::{
+    fs::File,
+    io::{BufReader, Cursor, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use super::{CompressedPageReader, CompressedPageReaderError, PageCompressionStats};
+
+enum CompressedPageFileContent {
+    File(PathBuf),
+    Mmap(memmap2::Mmap),
+}
+
+pub struct CompressedPageFile {
+    content: CompressedPageFileContent,
+    len: u64,
+}
+
+impl CompressedPageFile {
+    // File is read lazily - every reader opens its own handle and reads only the zip entries it needs
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, CompressedPageReaderError> {
+        let path = path.as_ref().to_path_buf();
+        let len = File::open(&path)?.metadata()?.len();
+
+        Ok(Self {
+            content: CompressedPageFileContent::File(path),
+            len,
+        })
+    }
+
+    /// Maps the page file into memory instead of reading it.
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the returned value is alive -
+    /// otherwise reading the mapped memory is undefined behaviour.
+    pub unsafe fn map(path: impl AsRef<Path>) -> Result<Self, CompressedPageReaderError> {
+        let file = File::open(path)?;
+
+        let mmap = memmap2::Mmap::map(&file)?;
+
+        Ok(Self {
+            len: mmap.len() as u64,
+            content: CompressedPageFileContent::Mmap(mmap),
+        })
+    }
+
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn get_seq_reader(
+        &self,
+    ) -> Result<CompressedPageFileReader<'_>, CompressedPageReaderError> {
+        let result = match &self.content {
+            CompressedPageFileContent::File(path) => {
+                CompressedPageFileReader::File(BufReader::new(File::open(path)?))
+            }
+            CompressedPageFileContent::Mmap(mmap) => {
+                CompressedPageFileReader::Mmap(Cursor::new(&mmap[..]))
+            }
+        };
+
+        Ok(result)
+    }
+
+    pub fn get_reader(
+        &self,
+    ) -> Result<CompressedPageReader<CompressedPageFileReader<'_>>, CompressedPageReaderError> {
+        CompressedPageReader::from_reader(self.get_seq_reader()?)
+    }
+
+    pub fn get_compression_stats(
+        &self,
+        largest_messages_amount: usize,
+    ) -> Result<PageCompressionStats, CompressedPageReaderError> {
+        CompressedPageReader::get_compression_stats_from_reader(
+            self.get_seq_reader()?,
+            largest_messages_amount,
+        )
+    }
+}
+
+pub enum CompressedPageFileReader<'s> {
+    File(BufReader<File>),
+    Mmap(Cursor<&'s [u8]>),
+}
+
+impl<'s> Read for CompressedPageFileReader<'s> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            CompressedPageFileReader::File(reader) => reader.read(buf),
+            CompressedPageFileReader::Mmap(reader) => reader.read(buf),
+        }
+    }
+}
+
+impl<'s> Seek for CompressedPageFileReader<'s> {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match self {
+            CompressedPageFileReader::File(reader) => reader.seek(pos),
+            CompressedPageFileReader::Mmap(reader) => reader.seek(pos),
+        }
+    }
+}
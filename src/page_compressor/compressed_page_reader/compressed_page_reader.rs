@@ -1,50 +1,183 @@
-use rust_extensions::{AsSliceOrVec, SliceOrVecSeqReader};
+use std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    io::{Read, Seek, SeekFrom},
+};
+
+use my_service_bus_abstractions::MessageId;
+use rust_extensions::{date_time::DateTimeAsMicroseconds, AsSliceOrVec, SliceOrVecSeqReader};
 
-use crate::protobuf_models::MessageProtobufModel;
+use crate::protobuf_models::{
+    MessageProtobufModel, SharedMessageProtobufModel, SharedMessagesProtobufModel,
+};
 
 use super::{
     CompressedPageReaderByFiles, CompressedPageReaderError, CompressedPageReaderSingleFile,
+    MessageSizeStats, PageCompressionStats,
 };
 
-pub enum CompressedPageReader<'s> {
-    ByFiles(CompressedPageReaderByFiles<'s>),
+pub enum CompressedPageReader<R: Read + Seek> {
+    ByFiles(CompressedPageReaderByFiles<R>),
     SingleFile(CompressedPageReaderSingleFile),
 }
 
-impl<'s> CompressedPageReader<'s> {
+impl<'s> CompressedPageReader<SliceOrVecSeqReader<'s, u8>> {
     pub fn new(zipped: impl Into<AsSliceOrVec<'s, u8>>) -> Result<Self, CompressedPageReaderError> {
-        let zipped: AsSliceOrVec<'_, u8> = zipped.into();
+        Self::from_reader(to_seq_reader(zipped))
+    }
 
-        let zipped: SliceOrVecSeqReader<'_, u8> = zipped.into();
+    pub fn read_shared_messages(
+        zipped: impl Into<AsSliceOrVec<'s, u8>>,
+    ) -> Result<Vec<SharedMessageProtobufModel>, CompressedPageReaderError> {
+        Self::read_shared_messages_from_reader(to_seq_reader(zipped))
+    }
 
-        let mut file_reader = CompressedPageReaderByFiles::new(zipped)?;
+    pub fn read_not_expired_shared_messages(
+        zipped: impl Into<AsSliceOrVec<'s, u8>>,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<Vec<SharedMessageProtobufModel>, CompressedPageReaderError> {
+        let mut result = Self::read_shared_messages(zipped)?;
+        result.retain(|itm| !itm.is_expired(now));
+        Ok(result)
+    }
 
-        let decompress_as_single_file = file_reader.decompress_as_single_file()?;
+    pub fn get_compression_stats(
+        zipped: impl Into<AsSliceOrVec<'s, u8>>,
+        largest_messages_amount: usize,
+    ) -> Result<PageCompressionStats, CompressedPageReaderError> {
+        Self::get_compression_stats_from_reader(to_seq_reader(zipped), largest_messages_amount)
+    }
+}
 
-        match decompress_as_single_file {
-            Some(messages) => {
-                let messages = messages.messages.into_iter().collect();
-                Ok(Self::SingleFile(CompressedPageReaderSingleFile::new(
-                    messages,
-                )?))
-            }
+// Zip entries are read from the reader on demand, so a page file does not have to be
+// loaded into memory as a whole.
+impl<R: Read + Seek> CompressedPageReader<R> {
+    pub fn from_reader(zipped: R) -> Result<Self, CompressedPageReaderError> {
+        let file_reader = CompressedPageReaderByFiles::new(zipped)?;
+
+        Self::from_file_reader(file_reader)
+    }
+
+    fn from_file_reader(
+        mut file_reader: CompressedPageReaderByFiles<R>,
+    ) -> Result<Self, CompressedPageReaderError> {
+        match file_reader.decompress_single_file_payload()? {
+            Some(page_buffer) => Ok(Self::SingleFile(CompressedPageReaderSingleFile::new(
+                page_buffer,
+            )?)),
 
             None => Ok(Self::ByFiles(file_reader)),
         }
     }
 
+    // Single file pages are decompressed into one buffer which backs the data of every message.
+    pub fn read_shared_messages_from_reader(
+        zipped: R,
+    ) -> Result<Vec<SharedMessageProtobufModel>, CompressedPageReaderError> {
+        let mut file_reader = CompressedPageReaderByFiles::new(zipped)?;
+
+        if let Some(page_buffer) = file_reader.decompress_single_file_payload()? {
+            let messages = SharedMessagesProtobufModel::parse(page_buffer.into())?;
+            return Ok(messages.messages);
+        }
+
+        let mut result = Vec::with_capacity(file_reader.get_files_amount());
+
+        while let Some(message) = file_reader.get_next_shared_message()? {
+            result.push(message);
+        }
+
+        Ok(result)
+    }
+
+    pub fn get_compression_stats_from_reader(
+        mut zipped: R,
+        largest_messages_amount: usize,
+    ) -> Result<PageCompressionStats, CompressedPageReaderError> {
+        let compressed_size = zipped.seek(SeekFrom::End(0))?;
+        zipped.seek(SeekFrom::Start(0))?;
+
+        let mut file_reader = CompressedPageReaderByFiles::new(zipped)?;
+
+        let entries = file_reader.get_entries_stats()?;
+
+        let mut reader = Self::from_file_reader(file_reader)?;
+
+        let mut messages_amount = 0;
+        let mut largest_messages = BinaryHeap::new();
+
+        while let Some(message) = reader.get_next_message()? {
+            messages_amount += 1;
+
+            let size = prost::Message::encoded_len(&message) as u64;
+
+            largest_messages.push(Reverse((size, message.get_message_id().get_value())));
+
+            if largest_messages.len() > largest_messages_amount {
+                largest_messages.pop();
+            }
+        }
+
+        let largest_messages = largest_messages
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((size, message_id))| MessageSizeStats { message_id, size })
+            .collect();
+
+        Ok(PageCompressionStats {
+            compressed_size,
+            uncompressed_size: entries.iter().map(|entry| entry.uncompressed_size).sum(),
+            messages_amount,
+            entries,
+            largest_messages,
+        })
+    }
+
     pub fn get_next_message(
         &mut self,
     ) -> Result<Option<MessageProtobufModel>, CompressedPageReaderError> {
         match self {
             CompressedPageReader::ByFiles(by_files) => by_files.get_next_message(),
             CompressedPageReader::SingleFile(by_single_file) => {
-                let result = by_single_file.get_next_message();
-                Ok(result)
+                Ok(by_single_file.get_next_message()?)
             }
         }
     }
 
+    pub fn get_next_not_expired_message(
+        &mut self,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<Option<MessageProtobufModel>, CompressedPageReaderError> {
+        while let Some(message) = self.get_next_message()? {
+            if !message.is_expired(now) {
+                return Ok(Some(message));
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub fn get_message(
+        &mut self,
+        message_id: MessageId,
+    ) -> Result<Option<MessageProtobufModel>, CompressedPageReaderError> {
+        match self {
+            CompressedPageReader::ByFiles(by_files) => by_files.get_message(message_id),
+            CompressedPageReader::SingleFile(by_single_file) => {
+                Ok(by_single_file.get_message(message_id)?)
+            }
+        }
+    }
+
+    pub fn get_not_expired_message(
+        &mut self,
+        message_id: MessageId,
+        now: DateTimeAsMicroseconds,
+    ) -> Result<Option<MessageProtobufModel>, CompressedPageReaderError> {
+        let result = self.get_message(message_id)?;
+        Ok(result.filter(|itm| !itm.is_expired(now)))
+    }
+
     pub fn get_files_amount(&self) -> usize {
         match self {
             CompressedPageReader::ByFiles(by_files) => by_files.get_files_amount(),
@@ -61,3 +194,209 @@ impl<'s> CompressedPageReader<'s> {
         }
     }
 }
+
+fn to_seq_reader<'s>(zipped: impl Into<AsSliceOrVec<'s, u8>>) -> SliceOrVecSeqReader<'s, u8> {
+    let zipped: AsSliceOrVec<'_, u8> = zipped.into();
+    zipped.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::page_compressor::{CompressedPageBuilder, CompressedPageFile};
+
+    use super::*;
+
+    fn build_page(mut builder: CompressedPageBuilder) -> Vec<u8> {
+        for (id, size) in [(1, 10), (2, 500), (3, 50), (4, 1000)] {
+            let msg = MessageProtobufModel::new(
+                id.into(),
+                DateTimeAsMicroseconds::now(),
+                vec![0u8; size],
+                vec![],
+            );
+            builder.add_message(&msg).unwrap();
+        }
+
+        builder.get_payload().unwrap()
+    }
+
+    #[test]
+    fn test_compression_stats_of_single_file_page() {
+        let payload = build_page(CompressedPageBuilder::new_as_single_file());
+
+        let stats = CompressedPageReader::get_compression_stats(payload.as_slice(), 2).unwrap();
+
+        assert_eq!(payload.len() as u64, stats.compressed_size);
+        assert_eq!(4, stats.messages_amount);
+        assert_eq!(1, stats.entries.len());
+        assert_eq!("d", stats.entries[0].name);
+        assert_eq!("Deflated", stats.entries[0].compression_method);
+        assert!(stats.get_ratio() < 1.0);
+
+        assert_eq!(2, stats.largest_messages.len());
+        assert_eq!(4, stats.largest_messages[0].message_id);
+        assert_eq!(2, stats.largest_messages[1].message_id);
+    }
+
+    #[test]
+    fn test_compression_stats_of_page_by_files() {
+        let payload = build_page(CompressedPageBuilder::new_by_files());
+
+        let stats = CompressedPageReader::get_compression_stats(payload, 10).unwrap();
+
+        assert_eq!(4, stats.messages_amount);
+        assert_eq!(4, stats.entries.len());
+        assert_eq!("1", stats.entries[0].name);
+        assert_eq!(4, stats.largest_messages.len());
+        assert_eq!(1, stats.largest_messages[3].message_id);
+
+        assert_eq!(
+            stats.uncompressed_size,
+            stats
+                .largest_messages
+                .iter()
+                .map(|itm| itm.size)
+                .sum::<u64>()
+        );
+    }
+
+    #[test]
+    fn test_reading_page_from_file() {
+        let payload = build_page(CompressedPageBuilder::new_by_files());
+
+        let file_name =
+            std::env::temp_dir().join(format!("my-service-bus-shared-{}.page", std::process::id()));
+
+        std::fs::write(&file_name, payload.as_slice()).unwrap();
+
+        let file = CompressedPageFile::open(&file_name).unwrap();
+
+        assert_eq!(payload.len() as u64, file.len());
+
+        let mut reader = file.get_reader().unwrap();
+
+        let msg = reader.get_message(3.into()).unwrap().unwrap();
+        assert_eq!(3, msg.get_message_id().get_value());
+        assert_eq!(50, msg.data.len());
+
+        assert!(reader.get_message(5.into()).unwrap().is_none());
+
+        // Readers of the same file do not share the position
+        let mut other_reader = file.get_reader().unwrap();
+        let msg = other_reader.get_next_message().unwrap().unwrap();
+        assert_eq!(1, msg.get_message_id().get_value());
+
+        let msg = reader.get_next_message().unwrap().unwrap();
+        assert_eq!(1, msg.get_message_id().get_value());
+
+        let stats = file.get_compression_stats(1).unwrap();
+        assert_eq!(payload.len() as u64, stats.compressed_size);
+        assert_eq!(4, stats.messages_amount);
+
+        drop(reader);
+        drop(other_reader);
+        drop(file);
+
+        let file = unsafe { CompressedPageFile::map(&file_name) }.unwrap();
+        assert_eq!(payload.len() as u64, file.len());
+
+        let msg = file
+            .get_reader()
+            .unwrap()
+            .get_message(4.into())
+            .unwrap()
+            .unwrap();
+        assert_eq!(1000, msg.data.len());
+        drop(file);
+
+        std::fs::remove_file(&file_name).unwrap();
+    }
+
+    #[test]
+    fn test_read_shared_messages() {
+        for builder in [
+            CompressedPageBuilder::new_as_single_file(),
+            CompressedPageBuilder::new_by_files(),
+        ] {
+            let payload = build_page(builder);
+
+            let messages = CompressedPageReader::read_shared_messages(payload).unwrap();
+
+            assert_eq!(4, messages.len());
+            assert_eq!(3, messages[2].get_message_id().get_value());
+            assert_eq!(50, messages[2].data.len());
+        }
+    }
+
+    #[test]
+    fn test_skipping_expired_messages() {
+        let mut builder = CompressedPageBuilder::new_by_files();
+
+        for id in 1..=3 {
+            let mut msg = MessageProtobufModel::new(
+                id.into(),
+                DateTimeAsMicroseconds::new(1_000_000),
+                vec![],
+                vec![],
+            );
+
+            if id == 2 {
+                msg.set_expires(Some(DateTimeAsMicroseconds::new(1_500_000)));
+            }
+
+            builder.add_message(&msg).unwrap();
+        }
+
+        let payload = builder.get_payload().unwrap();
+
+        let now = DateTimeAsMicroseconds::new(2_000_000);
+
+        let shared =
+            CompressedPageReader::read_not_expired_shared_messages(payload.as_slice(), now)
+                .unwrap();
+        assert_eq!(
+            vec![1, 3],
+            shared
+                .iter()
+                .map(|itm| itm.get_message_id().get_value())
+                .collect::<Vec<_>>()
+        );
+
+        let mut reader = CompressedPageReader::new(payload).unwrap();
+
+        assert!(reader
+            .get_not_expired_message(2.into(), now)
+            .unwrap()
+            .is_none());
+        assert!(reader
+            .get_not_expired_message(3.into(), now)
+            .unwrap()
+            .is_some());
+
+        let msg = reader.get_next_not_expired_message(now).unwrap().unwrap();
+        assert_eq!(1, msg.get_message_id().get_value());
+
+        let msg = reader.get_next_not_expired_message(now).unwrap().unwrap();
+        assert_eq!(3, msg.get_message_id().get_value());
+
+        assert!(reader.get_next_not_expired_message(now).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_get_message_from_single_file_page() {
+        let payload = build_page(CompressedPageBuilder::new_as_single_file());
+
+        let mut reader = CompressedPageReader::new(payload).unwrap();
+
+        let msg = reader.get_message(2.into()).unwrap().unwrap();
+        assert_eq!(500, msg.data.len());
+
+        assert!(reader.get_message(5.into()).unwrap().is_none());
+
+        while reader.get_next_message().unwrap().is_some() {}
+
+        let msg = reader.get_message(2.into()).unwrap().unwrap();
+        assert_eq!(2, msg.get_message_id().get_value());
+        assert_eq!(4, reader.get_messages_amount());
+    }
+}
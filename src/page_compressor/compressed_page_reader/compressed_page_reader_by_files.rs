@@ -1,19 +1,21 @@
-use std::io::Read;
+use std::io::{Read, Seek};
 
-use rust_extensions::SliceOrVecSeqReader;
+use my_service_bus_abstractions::MessageId;
 use zip::result::ZipError;
 
-use crate::protobuf_models::{MessageProtobufModel, MessagesProtobufModel};
+use crate::protobuf_models::{
+    MessageProtobufModel, MessagesProtobufModel, SharedMessageProtobufModel,
+};
 
-use super::CompressedPageReaderError;
+use super::{CompressedEntryStats, CompressedPageReaderError};
 
-pub struct CompressedPageReaderByFiles<'s> {
-    zip_archive: zip::ZipArchive<SliceOrVecSeqReader<'s, u8>>,
+pub struct CompressedPageReaderByFiles<R: Read + Seek> {
+    zip_archive: zip::ZipArchive<R>,
     file_index: usize,
 }
 
-impl<'s> CompressedPageReaderByFiles<'s> {
-    pub fn new(zipped: SliceOrVecSeqReader<'s, u8>) -> Result<Self, ZipError> {
+impl<R: Read + Seek> CompressedPageReaderByFiles<R> {
+    pub fn new(zipped: R) -> Result<Self, ZipError> {
         let zip_archive = zip::ZipArchive::new(zipped)?;
         Ok(Self {
             zip_archive,
@@ -25,6 +27,23 @@ impl<'s> CompressedPageReaderByFiles<'s> {
         return self.zip_archive.len();
     }
 
+    pub fn get_entries_stats(&mut self) -> Result<Vec<CompressedEntryStats>, ZipError> {
+        let mut result = Vec::with_capacity(self.zip_archive.len());
+
+        for index in 0..self.zip_archive.len() {
+            let zip_file = self.zip_archive.by_index(index)?;
+
+            result.push(CompressedEntryStats {
+                name: zip_file.name().to_string(),
+                compressed_size: zip_file.compressed_size(),
+                uncompressed_size: zip_file.size(),
+                compression_method: format!("{:?}", zip_file.compression()),
+            });
+        }
+
+        Ok(result)
+    }
+
     pub fn get_next_message(
         &mut self,
     ) -> Result<Option<MessageProtobufModel>, CompressedPageReaderError> {
@@ -32,28 +51,28 @@ impl<'s> CompressedPageReaderByFiles<'s> {
             return Ok(None);
         }
 
-        let mut zip_file = self.zip_archive.by_index(self.file_index)?;
-
-        let mut result_buffer: Vec<u8> = Vec::new();
+        let zip_file = self.zip_archive.by_index(self.file_index)?;
 
-        loop {
-            let mut buffer = [0u8; 1024 * 1024];
+        let result_buffer = read_zip_file(zip_file)?;
 
-            let read_size = zip_file.read(&mut buffer[..]);
+        self.file_index += 1;
 
-            if let Err(err) = read_size {
-                return Err(CompressedPageReaderError::ZipError(err.into()));
-            }
+        Ok(Some(MessageProtobufModel::parse(result_buffer.as_slice())?))
+    }
 
-            let read_size = read_size.unwrap();
+    pub fn get_message(
+        &mut self,
+        message_id: MessageId,
+    ) -> Result<Option<MessageProtobufModel>, CompressedPageReaderError> {
+        let file_name = format!("{}", message_id.get_value());
 
-            if read_size == 0 {
-                break;
-            }
+        let zip_file = match self.zip_archive.by_name(file_name.as_str()) {
+            Ok(zip_file) => zip_file,
+            Err(ZipError::FileNotFound) => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
 
-            result_buffer.extend(&buffer[..read_size]);
-        }
-        self.file_index += 1;
+        let result_buffer = read_zip_file(zip_file)?;
 
         Ok(Some(MessageProtobufModel::parse(result_buffer.as_slice())?))
     }
@@ -61,37 +80,67 @@ impl<'s> CompressedPageReaderByFiles<'s> {
     pub fn decompress_as_single_file(
         &mut self,
     ) -> Result<Option<MessagesProtobufModel>, CompressedPageReaderError> {
-        let mut page_buffer: Vec<u8> = Vec::new();
+        match self.decompress_single_file_payload()? {
+            Some(page_buffer) => Ok(Some(MessagesProtobufModel::parse(page_buffer.as_slice())?)),
+            None => Ok(None),
+        }
+    }
 
+    pub fn decompress_single_file_payload(
+        &mut self,
+    ) -> Result<Option<Vec<u8>>, CompressedPageReaderError> {
         if self.zip_archive.len() == 0 {
             return Err(CompressedPageReaderError::InvalidSingleFileCompressedPage);
         }
 
-        let mut zip_file = self.zip_archive.by_index(0)?;
+        let zip_file = self.zip_archive.by_index(0)?;
 
         if zip_file.name() != "d" {
             return Ok(None);
         }
 
-        let mut buffer = [0u8; 1024 * 1024];
+        Ok(Some(read_zip_file(zip_file)?))
+    }
 
-        loop {
-            let read_size = zip_file.read(&mut buffer[..]);
-            if let Err(err) = read_size {
-                return Err(CompressedPageReaderError::ZipError(err.into()));
-            }
+    pub fn get_next_shared_message(
+        &mut self,
+    ) -> Result<Option<SharedMessageProtobufModel>, CompressedPageReaderError> {
+        if self.file_index >= self.zip_archive.len() {
+            return Ok(None);
+        }
 
-            let read_size = read_size.unwrap();
+        let zip_file = self.zip_archive.by_index(self.file_index)?;
 
-            if read_size == 0 {
-                break;
-            }
+        let result_buffer = read_zip_file(zip_file)?;
 
-            page_buffer.extend(&buffer[..read_size]);
+        self.file_index += 1;
+
+        Ok(Some(SharedMessageProtobufModel::parse(
+            result_buffer.into(),
+        )?))
+    }
+}
+
+fn read_zip_file(mut zip_file: zip::read::ZipFile) -> Result<Vec<u8>, CompressedPageReaderError> {
+    let mut result_buffer: Vec<u8> = Vec::new();
+
+    loop {
+        let mut buffer = [0u8; 1024 * 1024];
+
+        let read_size = zip_file.read(&mut buffer[..]);
+
+        if let Err(err) = read_size {
+            return Err(CompressedPageReaderError::ZipError(err.into()));
         }
 
-        let result: MessagesProtobufModel = MessagesProtobufModel::parse(page_buffer.as_slice())?;
+        let read_size = read_size.unwrap();
+
+        if read_size == 0 {
+            break;
+        }
 
-        Ok(Some(result))
+        result_buffer.extend(&buffer[..read_size]);
     }
+
+    Ok(result_buffer)
 }
@@ -1,28 +1,112 @@
-use std::collections::VecDeque;
+use std::{collections::HashMap, ops::Range};
 
-use zip::result::ZipError;
+use my_service_bus_abstractions::MessageId;
+use prost::{
+    encoding::{decode_key, decode_varint, skip_field, DecodeContext, WireType},
+    DecodeError,
+};
 
 use crate::protobuf_models::MessageProtobufModel;
 
+const MESSAGES_TAG: u32 = 1;
+const MESSAGE_ID_TAG: u32 = 1;
+
+// Keeps the decompressed page as is and decodes messages on demand,
+// so a message can be read by id no matter how far the page has been iterated.
 pub struct CompressedPageReaderSingleFile {
-    messages: VecDeque<MessageProtobufModel>,
-    messages_amount: usize,
+    payload: Vec<u8>,
+    messages: Vec<Range<usize>>,
+    index: HashMap<i64, usize>,
+    position: usize,
 }
 
 impl CompressedPageReaderSingleFile {
-    pub fn new(messages: VecDeque<MessageProtobufModel>) -> Result<Self, ZipError> {
-        let messages_amount = messages.len();
+    pub fn new(payload: Vec<u8>) -> Result<Self, DecodeError> {
+        let messages = get_messages_ranges(payload.as_slice())?;
+
+        let mut index = HashMap::with_capacity(messages.len());
+
+        for (no, range) in messages.iter().enumerate() {
+            let message_id = read_message_id(&payload[range.clone()])?;
+            index.insert(message_id, no);
+        }
+
         Ok(Self {
+            payload,
             messages,
-            messages_amount,
+            index,
+            position: 0,
         })
     }
 
-    pub fn get_next_message(&mut self) -> Option<MessageProtobufModel> {
-        self.messages.pop_front()
+    pub fn get_next_message(&mut self) -> Result<Option<MessageProtobufModel>, DecodeError> {
+        if self.position >= self.messages.len() {
+            return Ok(None);
+        }
+
+        let result = self.decode_message(self.position)?;
+        self.position += 1;
+        Ok(Some(result))
+    }
+
+    pub fn get_message(
+        &self,
+        message_id: MessageId,
+    ) -> Result<Option<MessageProtobufModel>, DecodeError> {
+        match self.index.get(&message_id.get_value()) {
+            Some(no) => Ok(Some(self.decode_message(*no)?)),
+            None => Ok(None),
+        }
     }
 
     pub fn get_messages_amount(&self) -> usize {
-        self.messages_amount
+        self.messages.len()
+    }
+
+    fn decode_message(&self, no: usize) -> Result<MessageProtobufModel, DecodeError> {
+        MessageProtobufModel::parse(&self.payload[self.messages[no].clone()])
+    }
+}
+
+fn get_messages_ranges(payload: &[u8]) -> Result<Vec<Range<usize>>, DecodeError> {
+    let mut result = Vec::new();
+    let mut buf = payload;
+
+    while !buf.is_empty() {
+        let (tag, wire_type) = decode_key(&mut buf)?;
+
+        if tag != MESSAGES_TAG || wire_type != WireType::LengthDelimited {
+            skip_field(wire_type, tag, &mut buf, DecodeContext::default())?;
+            continue;
+        }
+
+        let len = decode_varint(&mut buf)? as usize;
+
+        if len > buf.len() {
+            return Err(DecodeError::new("buffer underflow"));
+        }
+
+        let start = payload.len() - buf.len();
+        result.push(start..start + len);
+        buf = &buf[len..];
+    }
+
+    Ok(result)
+}
+
+fn read_message_id(mut buf: &[u8]) -> Result<i64, DecodeError> {
+    let mut result = 0;
+
+    // Last value wins - the same way prost merges a repeated scalar field
+    while !buf.is_empty() {
+        let (tag, wire_type) = decode_key(&mut buf)?;
+
+        if tag == MESSAGE_ID_TAG && wire_type == WireType::Varint {
+            result = decode_varint(&mut buf)? as i64;
+        } else {
+            skip_field(wire_type, tag, &mut buf, DecodeContext::default())?;
+        }
     }
+
+    Ok(result)
 }
@@ -9,6 +9,7 @@ pub enum CompressedPageReaderError {
     ZipError(ZipError),
     InvalidSingleFileCompressedPage,
     DecodeError(DecodeError),
+    IoError(std::io::Error),
 }
 
 impl From<ZipError> for CompressedPageReaderError {
@@ -28,3 +29,9 @@ impl From<DecodeError> for CompressedPageReaderError {
         Self::DecodeError(src)
     }
 }
+
+impl From<std::io::Error> for CompressedPageReaderError {
+    fn from(src: std::io::Error) -> Self {
+        Self::IoError(src)
+    }
+}
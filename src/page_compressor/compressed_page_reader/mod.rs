@@ -1,8 +1,12 @@
+mod compressed_page_file;
 mod compressed_page_reader;
 mod compressed_page_reader_by_files;
 mod compressed_page_reader_single_file;
+pub use compressed_page_file::*;
 pub use compressed_page_reader::*;
 mod error;
+mod page_compression_stats;
 pub use compressed_page_reader_by_files::*;
 pub use compressed_page_reader_single_file::*;
 pub use error::*;
+pub use page_compression_stats::*;
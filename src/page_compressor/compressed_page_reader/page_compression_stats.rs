@@ -0,0 +1,66 @@
+use prost::{DecodeError, EncodeError};
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PageCompressionStats {
+    #[prost(uint64, tag = "1")]
+    pub compressed_size: u64,
+    #[prost(uint64, tag = "2")]
+    pub uncompressed_size: u64,
+    #[prost(uint64, tag = "3")]
+    pub messages_amount: u64,
+    #[prost(message, repeated, tag = "4")]
+    pub entries: Vec<CompressedEntryStats>,
+    #[prost(message, repeated, tag = "5")]
+    pub largest_messages: Vec<MessageSizeStats>,
+}
+
+impl PageCompressionStats {
+    pub fn get_ratio(&self) -> f64 {
+        calc_ratio(self.compressed_size, self.uncompressed_size)
+    }
+
+    pub fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        prost::Message::decode(payload)
+    }
+
+    pub fn serialize(&self, dest: &mut Vec<u8>) -> Result<(), EncodeError> {
+        prost::Message::encode(self, dest)
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CompressedEntryStats {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(uint64, tag = "2")]
+    pub compressed_size: u64,
+    #[prost(uint64, tag = "3")]
+    pub uncompressed_size: u64,
+    #[prost(string, tag = "4")]
+    pub compression_method: String,
+}
+
+impl CompressedEntryStats {
+    pub fn get_ratio(&self) -> f64 {
+        calc_ratio(self.compressed_size, self.uncompressed_size)
+    }
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MessageSizeStats {
+    #[prost(int64, tag = "1")]
+    pub message_id: i64,
+    #[prost(uint64, tag = "2")]
+    pub size: u64,
+}
+
+fn calc_ratio(compressed_size: u64, uncompressed_size: u64) -> f64 {
+    if uncompressed_size == 0 {
+        return 0.0;
+    }
+
+    compressed_size as f64 / uncompressed_size as f64
+}
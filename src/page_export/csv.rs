@@ -0,0 +1,430 @@
+use std::{
+    io::{BufRead, Read, Seek, Write},
+    time::Duration,
+};
+
+use serde_json::Value;
+
+use crate::page_compressor::{CompressedPageBuilder, CompressedPageReader};
+
+use super::{
+    headers_from_json, headers_to_json, DataEncoding, ExportedMessage, PageExportError,
+    PageImportError,
+};
+
+pub const CSV_COLUMNS: [&str; 10] = [
+    "id",
+    "created",
+    "expires",
+    "ttl_micros",
+    "partition_key",
+    "correlation_id",
+    "content_type",
+    "headers",
+    "data",
+    "encoding",
+];
+
+pub fn export_as_csv(
+    reader: &mut CompressedPageReader<impl Read + Seek>,
+    out: &mut impl Write,
+) -> Result<usize, PageExportError> {
+    write_csv_record(out, &CSV_COLUMNS.map(Some))?;
+
+    let mut amount = 0;
+
+    while let Some(message) = reader.get_next_message()? {
+        let message = ExportedMessage::from_model(&message);
+
+        let ttl = message.ttl.map(|itm| {
+            u64::try_from(itm.as_micros())
+                .unwrap_or(u64::MAX)
+                .to_string()
+        });
+
+        write_csv_record(
+            out,
+            &[
+                Some(message.id.to_string().as_str()),
+                Some(message.created.as_str()),
+                message.expires.as_deref(),
+                ttl.as_deref(),
+                message.partition_key.as_deref(),
+                message.correlation_id.as_deref(),
+                message.content_type.as_deref(),
+                Some(headers_to_json(&message.headers).to_string().as_str()),
+                Some(message.data.as_str()),
+                Some(message.encoding.as_str()),
+            ],
+        )?;
+
+        amount += 1;
+    }
+
+    Ok(amount)
+}
+
+pub fn import_from_csv(
+    input: impl BufRead,
+    builder: &mut CompressedPageBuilder,
+) -> Result<usize, PageImportError> {
+    let mut records = CsvRecords::new(input);
+
+    match records.next().transpose()? {
+        Some((_, columns))
+            if columns
+                .iter()
+                .map(|itm| itm.as_deref().unwrap_or_default())
+                .eq(CSV_COLUMNS) => {}
+        _ => {
+            return Err(PageImportError::invalid_record(
+                1,
+                format!("Header must be: {}", CSV_COLUMNS.join(",")),
+            ))
+        }
+    }
+
+    let mut amount = 0;
+
+    for record in records {
+        let (line, fields) = record?;
+
+        let message = parse_csv_record(fields)
+            .map_err(|reason| PageImportError::invalid_record(line, reason))?;
+
+        let model = message
+            .to_model()
+            .map_err(|reason| PageImportError::invalid_record(line, reason))?;
+
+        builder.add_message(&model)?;
+        amount += 1;
+    }
+
+    Ok(amount)
+}
+
+// An unquoted empty field is an absent value, a quoted one ("") is an empty string.
+fn parse_csv_record(fields: Vec<Option<String>>) -> Result<ExportedMessage, String> {
+    if fields.len() != CSV_COLUMNS.len() {
+        return Err(format!(
+            "Expected {} fields but found {}",
+            CSV_COLUMNS.len(),
+            fields.len()
+        ));
+    }
+
+    let id = fields[0].as_deref().unwrap_or_default();
+    let id = id
+        .parse::<i64>()
+        .map_err(|_| format!("Invalid id: {}", id))?;
+
+    let ttl = match fields[3].as_deref() {
+        Some(ttl) => {
+            let micros = ttl
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid ttl_micros: {}", ttl))?;
+            Some(Duration::from_micros(micros))
+        }
+        None => None,
+    };
+
+    let headers = match fields[7].as_deref() {
+        Some(headers) => {
+            let src: Value =
+                serde_json::from_str(headers).map_err(|err| format!("Invalid headers: {}", err))?;
+            headers_from_json(&src)?
+        }
+        None => Vec::new(),
+    };
+
+    let encoding = fields[9].as_deref().unwrap_or_default();
+    let encoding =
+        DataEncoding::parse(encoding).ok_or_else(|| format!("Invalid encoding: {}", encoding))?;
+
+    Ok(ExportedMessage {
+        id,
+        created: fields[1].clone().unwrap_or_default(),
+        expires: fields[2].clone(),
+        ttl,
+        partition_key: fields[4].clone(),
+        correlation_id: fields[5].clone(),
+        content_type: fields[6].clone(),
+        headers,
+        data: fields[8].clone().unwrap_or_default(),
+        encoding,
+    })
+}
+
+fn write_csv_record(out: &mut impl Write, fields: &[Option<&str>]) -> std::io::Result<()> {
+    for (index, field) in fields.iter().enumerate() {
+        if index > 0 {
+            out.write_all(b",")?;
+        }
+
+        match field {
+            Some(field) if field.is_empty() || field.contains([',', '"', '\n', '\r']) => {
+                out.write_all(b"\"")?;
+                out.write_all(field.replace('"', "\"\"").as_bytes())?;
+                out.write_all(b"\"")?;
+            }
+            Some(field) => out.write_all(field.as_bytes())?,
+            None => {}
+        }
+    }
+
+    out.write_all(b"\n")
+}
+
+type CsvRecord = (usize, Vec<Option<String>>);
+
+// Yields records together with the line they start at. Quoted fields may contain line breaks,
+// so a record is read line by line until its quotes are closed.
+struct CsvRecords<R: BufRead> {
+    input: R,
+    line: usize,
+}
+
+impl<R: BufRead> CsvRecords<R> {
+    fn new(input: R) -> Self {
+        Self { input, line: 0 }
+    }
+
+    fn read_line(&mut self, buffer: &mut String) -> std::io::Result<bool> {
+        buffer.clear();
+
+        if self.input.read_line(buffer)? == 0 {
+            return Ok(false);
+        }
+
+        self.line += 1;
+        Ok(true)
+    }
+
+    fn read_record(&mut self) -> Result<Option<CsvRecord>, PageImportError> {
+        let mut buffer = String::new();
+
+        loop {
+            if !self.read_line(&mut buffer)? {
+                return Ok(None);
+            }
+
+            if !buffer.trim_end_matches(['\r', '\n']).is_empty() {
+                break;
+            }
+        }
+
+        let line = self.line;
+        let mut fields = Vec::new();
+        let mut field = String::new();
+        let mut has_quotes = false;
+        let mut quoted = false;
+
+        loop {
+            let mut chars = buffer.chars().peekable();
+
+            while let Some(c) = chars.next() {
+                if quoted {
+                    match c {
+                        '"' if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        '"' => quoted = false,
+                        c => field.push(c),
+                    }
+
+                    continue;
+                }
+
+                match c {
+                    '"' => {
+                        has_quotes = true;
+                        quoted = true;
+                    }
+                    ',' => fields.push(take_field(&mut field, &mut has_quotes)),
+                    '\r' | '\n' => {}
+                    c => field.push(c),
+                }
+            }
+
+            if !quoted || !self.read_line(&mut buffer)? {
+                break;
+            }
+        }
+
+        if quoted {
+            return Err(PageImportError::invalid_record(
+                line,
+                "Quoted field is not terminated",
+            ));
+        }
+
+        fields.push(take_field(&mut field, &mut has_quotes));
+
+        Ok(Some((line, fields)))
+    }
+}
+
+fn take_field(field: &mut String, has_quotes: &mut bool) -> Option<String> {
+    let quoted = std::mem::take(has_quotes);
+    Some(std::mem::take(field)).filter(|itm| quoted || !itm.is_empty())
+}
+
+impl<R: BufRead> Iterator for CsvRecords<R> {
+    type Item = Result<CsvRecord, PageImportError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.read_record().transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::protobuf_models::{MessageMetaDataProtobufModel, MessageProtobufModel};
+
+    use super::*;
+
+    #[test]
+    fn test_export_and_import_csv() {
+        let mut builder = CompressedPageBuilder::new_as_single_file();
+
+        builder
+            .add_message(&MessageProtobufModel::new(
+                1.into(),
+                DateTimeAsMicroseconds::new(1_600_000_000_000_000),
+                "line 1,\n\"line 2\"".as_bytes().to_vec(),
+                vec![MessageMetaDataProtobufModel {
+                    key: "key".to_string(),
+                    value: "value".to_string(),
+                }],
+            ))
+            .unwrap();
+
+        builder
+            .add_message(&MessageProtobufModel::new(
+                2.into(),
+                DateTimeAsMicroseconds::new(1_600_000_000_000_001),
+                vec![0xff],
+                vec![],
+            ))
+            .unwrap();
+
+        let mut reader = CompressedPageReader::new(builder.get_payload().unwrap()).unwrap();
+
+        let mut exported = Vec::new();
+        assert_eq!(2, export_as_csv(&mut reader, &mut exported).unwrap());
+
+        let exported = String::from_utf8(exported).unwrap();
+
+        assert_eq!(
+            "id,created,expires,ttl_micros,partition_key,correlation_id,content_type,headers,data,encoding\n1,2020-09-13T12:26:40.000000Z,,,,,,\"{\"\"key\"\":\"\"value\"\"}\",\"line 1,\n\"\"line 2\"\"\",utf8\n2,2020-09-13T12:26:40.000001Z,,,,,,{},/w==,base64\n",
+            exported
+        );
+
+        let mut builder = CompressedPageBuilder::new_by_files();
+        assert_eq!(
+            2,
+            import_from_csv(exported.as_bytes(), &mut builder).unwrap()
+        );
+
+        let mut reader = CompressedPageReader::new(builder.get_payload().unwrap()).unwrap();
+
+        let msg = reader.get_next_message().unwrap().unwrap();
+        assert_eq!("line 1,\n\"line 2\"".as_bytes(), msg.data.as_slice());
+        assert_eq!("key", msg.headers[0].key);
+
+        let msg = reader.get_next_message().unwrap().unwrap();
+        assert_eq!(vec![0xff], msg.data);
+        assert_eq!(1_600_000_000_000_001, msg.get_created().unix_microseconds);
+    }
+
+    #[test]
+    fn test_import_reports_line_of_the_record() {
+        let src = "id,created,expires,ttl_micros,partition_key,correlation_id,content_type,headers,data,encoding\n1,2020-09-13T12:26:40Z,,,,,,,\"a\nb\",utf8\nx,2020-09-13T12:26:40Z,,,,,,,a,utf8\n";
+
+        let mut builder = CompressedPageBuilder::new_as_single_file();
+
+        match import_from_csv(src.as_bytes(), &mut builder) {
+            Err(PageImportError::InvalidRecord { line, .. }) => assert_eq!(4, line),
+            _ => panic!("Should not be here"),
+        }
+    }
+
+    #[test]
+    fn test_optional_fields_survive_csv_round_trip() {
+        let mut model = MessageProtobufModel::new(
+            1.into(),
+            DateTimeAsMicroseconds::new(1_600_000_000_000_000),
+            vec![],
+            vec![],
+        );
+        model.set_expires(Some(DateTimeAsMicroseconds::new(1_600_000_001_000_000)));
+        model.set_ttl(Some(std::time::Duration::from_micros(1_500)));
+        model.set_partition_key(Some("partition".to_string()));
+        model.set_content_type(Some("text/plain".to_string()));
+
+        let mut builder = CompressedPageBuilder::new_as_single_file();
+        builder.add_message(&model).unwrap();
+
+        let mut reader = CompressedPageReader::new(builder.get_payload().unwrap()).unwrap();
+
+        let mut exported = Vec::new();
+        export_as_csv(&mut reader, &mut exported).unwrap();
+
+        let mut builder = CompressedPageBuilder::new_as_single_file();
+        import_from_csv(exported.as_slice(), &mut builder).unwrap();
+
+        let mut reader = CompressedPageReader::new(builder.get_payload().unwrap()).unwrap();
+
+        assert_eq!(model, reader.get_next_message().unwrap().unwrap());
+    }
+
+    #[test]
+    fn test_empty_optional_fields_survive_csv_round_trip() {
+        let mut model = MessageProtobufModel::new(
+            1.into(),
+            DateTimeAsMicroseconds::new(1_600_000_000_000_000),
+            vec![],
+            vec![],
+        );
+        model.set_partition_key(Some("".to_string()));
+        model.set_content_type(Some("".to_string()));
+
+        let mut builder = CompressedPageBuilder::new_as_single_file();
+        builder.add_message(&model).unwrap();
+
+        let mut reader = CompressedPageReader::new(builder.get_payload().unwrap()).unwrap();
+
+        let mut exported = Vec::new();
+        export_as_csv(&mut reader, &mut exported).unwrap();
+
+        assert!(String::from_utf8(exported.clone())
+            .unwrap()
+            .ends_with("\n1,2020-09-13T12:26:40.000000Z,,,\"\",,\"\",{},\"\",utf8\n"));
+
+        let mut builder = CompressedPageBuilder::new_as_single_file();
+        import_from_csv(exported.as_slice(), &mut builder).unwrap();
+
+        let mut reader = CompressedPageReader::new(builder.get_payload().unwrap()).unwrap();
+        let msg = reader.get_next_message().unwrap().unwrap();
+
+        assert_eq!(Some(""), msg.get_partition_key());
+        assert_eq!(None, msg.get_correlation_id());
+        assert_eq!(Some(""), msg.get_content_type());
+        assert_eq!(model, msg);
+    }
+
+    #[test]
+    fn test_import_rejects_unterminated_quote() {
+        let src = "id,created,expires,ttl_micros,partition_key,correlation_id,content_type,headers,data,encoding\n1,2020-09-13T12:26:40Z,,,,,,,\"a\nb,utf8\n";
+
+        let mut builder = CompressedPageBuilder::new_as_single_file();
+
+        match import_from_csv(src.as_bytes(), &mut builder) {
+            Err(PageImportError::InvalidRecord { line, .. }) => assert_eq!(2, line),
+            _ => panic!("Should not be here"),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use crate::page_compressor::{CompressedPageReaderError, CompressedPageWriterError};
+
+#[derive(Debug)]
+pub enum PageExportError {
+    ReaderError(CompressedPageReaderError),
+    IoError(std::io::Error),
+}
+
+impl From<CompressedPageReaderError> for PageExportError {
+    fn from(src: CompressedPageReaderError) -> Self {
+        Self::ReaderError(src)
+    }
+}
+
+impl From<std::io::Error> for PageExportError {
+    fn from(src: std::io::Error) -> Self {
+        Self::IoError(src)
+    }
+}
+
+#[derive(Debug)]
+pub enum PageImportError {
+    IoError(std::io::Error),
+    WriterError(CompressedPageWriterError),
+    InvalidRecord { line: usize, reason: String },
+}
+
+impl PageImportError {
+    pub fn invalid_record(line: usize, reason: impl Into<String>) -> Self {
+        Self::InvalidRecord {
+            line,
+            reason: reason.into(),
+        }
+    }
+}
+
+impl From<std::io::Error> for PageImportError {
+    fn from(src: std::io::Error) -> Self {
+        Self::IoError(src)
+    }
+}
+
+impl From<CompressedPageWriterError> for PageImportError {
+    fn from(src: CompressedPageWriterError) -> Self {
+        Self::WriterError(src)
+    }
+}
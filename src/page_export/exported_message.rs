@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use base64::Engine;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+use serde_json::{Map, Value};
+
+use crate::protobuf_models::{MessageMetaDataProtobufModel, MessageProtobufModel};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataEncoding {
+    Utf8,
+    Base64,
+}
+
+impl DataEncoding {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataEncoding::Utf8 => "utf8",
+            DataEncoding::Base64 => "base64",
+        }
+    }
+
+    pub fn parse(src: &str) -> Option<Self> {
+        match src {
+            "utf8" => Some(DataEncoding::Utf8),
+            "base64" => Some(DataEncoding::Base64),
+            _ => None,
+        }
+    }
+}
+
+// Flat textual form of a message, shared by JSON Lines and CSV exports.
+#[derive(Debug, Clone)]
+pub struct ExportedMessage {
+    pub id: i64,
+    pub created: String,
+    pub expires: Option<String>,
+    pub ttl: Option<Duration>,
+    pub partition_key: Option<String>,
+    pub correlation_id: Option<String>,
+    pub content_type: Option<String>,
+    pub headers: Vec<(String, String)>,
+    pub data: String,
+    pub encoding: DataEncoding,
+}
+
+impl ExportedMessage {
+    pub fn from_model(model: &MessageProtobufModel) -> Self {
+        let (data, encoding) = match std::str::from_utf8(model.data.as_slice()) {
+            Ok(data) => (data.to_string(), DataEncoding::Utf8),
+            Err(_) => (
+                base64::engine::general_purpose::STANDARD.encode(model.data.as_slice()),
+                DataEncoding::Base64,
+            ),
+        };
+
+        Self {
+            id: model.get_message_id().get_value(),
+            created: format_rfc3339(model.get_created()),
+            expires: model.get_expires().map(format_rfc3339),
+            ttl: model.get_ttl(),
+            partition_key: model.get_partition_key().map(|itm| itm.to_string()),
+            correlation_id: model.get_correlation_id().map(|itm| itm.to_string()),
+            content_type: model.get_content_type().map(|itm| itm.to_string()),
+            headers: model
+                .headers
+                .iter()
+                .map(|itm| (itm.key.to_string(), itm.value.to_string()))
+                .collect(),
+            data,
+            encoding,
+        }
+    }
+
+    pub fn to_model(&self) -> Result<MessageProtobufModel, String> {
+        let created = parse_rfc3339(self.created.as_str())
+            .ok_or_else(|| format!("Invalid created date: {}", self.created))?;
+
+        let expires = match self.expires.as_deref() {
+            Some(expires) => Some(
+                parse_rfc3339(expires)
+                    .ok_or_else(|| format!("Invalid expires date: {}", expires))?,
+            ),
+            None => None,
+        };
+
+        let data = match self.encoding {
+            DataEncoding::Utf8 => self.data.as_bytes().to_vec(),
+            DataEncoding::Base64 => base64::engine::general_purpose::STANDARD
+                .decode(self.data.as_str())
+                .map_err(|err| format!("Invalid base64 data: {}", err))?,
+        };
+
+        let headers = self
+            .headers
+            .iter()
+            .map(|(key, value)| MessageMetaDataProtobufModel {
+                key: key.to_string(),
+                value: value.to_string(),
+            })
+            .collect();
+
+        let mut result = MessageProtobufModel::new(self.id.into(), created, data, headers);
+        result.set_expires(expires);
+        result.set_ttl(self.ttl);
+        result.set_partition_key(self.partition_key.clone());
+        result.set_correlation_id(self.correlation_id.clone());
+        result.set_content_type(self.content_type.clone());
+
+        Ok(result)
+    }
+}
+
+// Headers are exported as an object. Duplicate keys follow the protobuf model policy:
+// the first one wins and the rest are dropped.
+pub fn headers_to_json(headers: &[(String, String)]) -> Value {
+    let mut result = Map::new();
+
+    for (key, value) in headers {
+        result
+            .entry(key.to_string())
+            .or_insert_with(|| Value::String(value.to_string()));
+    }
+
+    Value::Object(result)
+}
+
+pub fn headers_from_json(src: &Value) -> Result<Vec<(String, String)>, String> {
+    let src = match src {
+        Value::Object(src) => src,
+        Value::Null => return Ok(Vec::new()),
+        _ => return Err("Headers must be an object of string values".to_string()),
+    };
+
+    let mut result = Vec::with_capacity(src.len());
+
+    for (key, value) in src {
+        match value {
+            Value::String(value) => result.push((key.to_string(), value.to_string())),
+            _ => return Err(format!("Header '{}' must be a string", key)),
+        }
+    }
+
+    Ok(result)
+}
+
+// Dates chrono can not represent are written as raw microseconds and parsed back the same way.
+pub fn format_rfc3339(date_time: DateTimeAsMicroseconds) -> String {
+    match chrono::DateTime::from_timestamp_micros(date_time.unix_microseconds) {
+        Some(result) => result.to_rfc3339_opts(chrono::SecondsFormat::Micros, true),
+        None => date_time.unix_microseconds.to_string(),
+    }
+}
+
+pub fn parse_rfc3339(src: &str) -> Option<DateTimeAsMicroseconds> {
+    match chrono::DateTime::parse_from_rfc3339(src) {
+        Ok(result) => Some(DateTimeAsMicroseconds::new(result.timestamp_micros())),
+        Err(_) => src.parse().ok().map(DateTimeAsMicroseconds::new),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc3339_round_trip() {
+        for micros in [0, 1_600_000_000_123_456, -1, i64::MIN, i64::MAX] {
+            let formatted = format_rfc3339(DateTimeAsMicroseconds::new(micros));
+
+            assert_eq!(
+                micros,
+                parse_rfc3339(formatted.as_str()).unwrap().unix_microseconds
+            );
+        }
+    }
+
+    #[test]
+    fn test_model_round_trip_keeps_all_fields() {
+        let mut model = MessageProtobufModel::new(
+            7.into(),
+            DateTimeAsMicroseconds::new(1_000_000),
+            vec![1, 2, 3],
+            vec![
+                MessageMetaDataProtobufModel {
+                    key: "b".to_string(),
+                    value: "1".to_string(),
+                },
+                MessageMetaDataProtobufModel {
+                    key: "a".to_string(),
+                    value: "2".to_string(),
+                },
+                MessageMetaDataProtobufModel {
+                    key: "b".to_string(),
+                    value: "3".to_string(),
+                },
+            ],
+        );
+        model.set_expires(Some(DateTimeAsMicroseconds::new(5_000_000)));
+        model.set_ttl(Some(Duration::from_millis(1500)));
+        model.set_partition_key(Some("partition".to_string()));
+        model.set_correlation_id(Some("correlation".to_string()));
+        model.set_content_type(Some("application/octet-stream".to_string()));
+
+        let exported = ExportedMessage::from_model(&model);
+
+        let headers = headers_from_json(&headers_to_json(&exported.headers)).unwrap();
+        assert_eq!(
+            vec![
+                ("a".to_string(), "2".to_string()),
+                ("b".to_string(), "1".to_string())
+            ],
+            headers
+        );
+
+        assert_eq!(model, exported.to_model().unwrap());
+    }
+
+    #[test]
+    fn test_headers_are_exported_as_object() {
+        let headers = vec![
+            ("key".to_string(), "first".to_string()),
+            ("key".to_string(), "second".to_string()),
+        ];
+
+        assert_eq!("{\"key\":\"first\"}", headers_to_json(&headers).to_string());
+
+        let src: Value = serde_json::from_str("[[\"key\",\"value\"]]").unwrap();
+        assert!(headers_from_json(&src).is_err());
+
+        let src: Value = serde_json::from_str("{\"key\":1}").unwrap();
+        assert!(headers_from_json(&src).is_err());
+    }
+}
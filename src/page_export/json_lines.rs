@@ -0,0 +1,238 @@
+use std::{
+    io::{BufRead, Read, Seek, Write},
+    time::Duration,
+};
+
+use serde_json::{Map, Value};
+
+use crate::page_compressor::{CompressedPageBuilder, CompressedPageReader};
+
+use super::{
+    headers_from_json, headers_to_json, DataEncoding, ExportedMessage, PageExportError,
+    PageImportError,
+};
+
+pub fn export_as_json_lines(
+    reader: &mut CompressedPageReader<impl Read + Seek>,
+    out: &mut impl Write,
+) -> Result<usize, PageExportError> {
+    let mut amount = 0;
+
+    while let Some(message) = reader.get_next_message()? {
+        let line = to_json_line(&ExportedMessage::from_model(&message));
+        out.write_all(line.as_bytes())?;
+        out.write_all(b"\n")?;
+        amount += 1;
+    }
+
+    Ok(amount)
+}
+
+pub fn import_from_json_lines(
+    input: impl BufRead,
+    builder: &mut CompressedPageBuilder,
+) -> Result<usize, PageImportError> {
+    let mut amount = 0;
+
+    for (index, line) in input.lines().enumerate() {
+        let line = line?;
+        let line_no = index + 1;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let message = from_json_line(line.as_str())
+            .map_err(|reason| PageImportError::invalid_record(line_no, reason))?;
+
+        let model = message
+            .to_model()
+            .map_err(|reason| PageImportError::invalid_record(line_no, reason))?;
+
+        builder.add_message(&model)?;
+        amount += 1;
+    }
+
+    Ok(amount)
+}
+
+pub fn to_json_line(message: &ExportedMessage) -> String {
+    let mut result = Map::new();
+    result.insert("id".to_string(), Value::from(message.id));
+    result.insert(
+        "created".to_string(),
+        Value::String(message.created.to_string()),
+    );
+
+    if let Some(expires) = &message.expires {
+        result.insert("expires".to_string(), Value::String(expires.to_string()));
+    }
+
+    if let Some(ttl) = message.ttl {
+        result.insert(
+            "ttl_micros".to_string(),
+            Value::from(u64::try_from(ttl.as_micros()).unwrap_or(u64::MAX)),
+        );
+    }
+
+    for (key, value) in [
+        ("partition_key", &message.partition_key),
+        ("correlation_id", &message.correlation_id),
+        ("content_type", &message.content_type),
+    ] {
+        if let Some(value) = value {
+            result.insert(key.to_string(), Value::String(value.to_string()));
+        }
+    }
+
+    result.insert("headers".to_string(), headers_to_json(&message.headers));
+    result.insert("data".to_string(), Value::String(message.data.to_string()));
+    result.insert(
+        "encoding".to_string(),
+        Value::String(message.encoding.as_str().to_string()),
+    );
+
+    Value::Object(result).to_string()
+}
+
+pub fn from_json_line(line: &str) -> Result<ExportedMessage, String> {
+    let value: Value = serde_json::from_str(line).map_err(|err| err.to_string())?;
+
+    let id = value
+        .get("id")
+        .and_then(|itm| itm.as_i64())
+        .ok_or("Field 'id' must be an integer")?;
+
+    let created = value
+        .get("created")
+        .and_then(|itm| itm.as_str())
+        .ok_or("Field 'created' must be a string")?;
+
+    let data = value
+        .get("data")
+        .and_then(|itm| itm.as_str())
+        .ok_or("Field 'data' must be a string")?;
+
+    let encoding = match value.get("encoding") {
+        Some(encoding) => encoding
+            .as_str()
+            .and_then(DataEncoding::parse)
+            .ok_or("Field 'encoding' must be 'utf8' or 'base64'")?,
+        None => DataEncoding::Utf8,
+    };
+
+    let ttl = match value.get("ttl_micros") {
+        Some(Value::Null) | None => None,
+        Some(ttl) => Some(Duration::from_micros(
+            ttl.as_u64()
+                .ok_or("Field 'ttl_micros' must be a positive integer")?,
+        )),
+    };
+
+    let headers = match value.get("headers") {
+        Some(headers) => headers_from_json(headers)?,
+        None => Vec::new(),
+    };
+
+    Ok(ExportedMessage {
+        id,
+        created: created.to_string(),
+        expires: get_optional_string(&value, "expires")?,
+        ttl,
+        partition_key: get_optional_string(&value, "partition_key")?,
+        correlation_id: get_optional_string(&value, "correlation_id")?,
+        content_type: get_optional_string(&value, "content_type")?,
+        headers,
+        data: data.to_string(),
+        encoding,
+    })
+}
+
+fn get_optional_string(value: &Value, field: &str) -> Result<Option<String>, String> {
+    match value.get(field) {
+        Some(Value::String(result)) => Ok(Some(result.to_string())),
+        Some(Value::Null) | None => Ok(None),
+        Some(_) => Err(format!("Field '{}' must be a string", field)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::protobuf_models::{MessageMetaDataProtobufModel, MessageProtobufModel};
+
+    use super::*;
+
+    #[test]
+    fn test_export_and_import_json_lines() {
+        let mut builder = CompressedPageBuilder::new_as_single_file();
+
+        builder
+            .add_message(&MessageProtobufModel::new(
+                1.into(),
+                DateTimeAsMicroseconds::new(1_600_000_000_123_456),
+                "{\"text\":\"hello\"}".as_bytes().to_vec(),
+                vec![MessageMetaDataProtobufModel {
+                    key: "content-type".to_string(),
+                    value: "application/json".to_string(),
+                }],
+            ))
+            .unwrap();
+
+        builder
+            .add_message(&MessageProtobufModel::new(
+                2.into(),
+                DateTimeAsMicroseconds::new(1_600_000_000_000_000),
+                vec![0xff, 0xfe, 0x00],
+                vec![],
+            ))
+            .unwrap();
+
+        let payload = builder.get_payload().unwrap();
+        let mut reader = CompressedPageReader::new(payload).unwrap();
+
+        let mut exported = Vec::new();
+        assert_eq!(2, export_as_json_lines(&mut reader, &mut exported).unwrap());
+
+        let exported = String::from_utf8(exported).unwrap();
+        let lines: Vec<&str> = exported.lines().collect();
+
+        assert_eq!(
+            "{\"created\":\"2020-09-13T12:26:40.123456Z\",\"data\":\"{\\\"text\\\":\\\"hello\\\"}\",\"encoding\":\"utf8\",\"headers\":{\"content-type\":\"application/json\"},\"id\":1}",
+            lines[0]
+        );
+        assert!(lines[1].contains("\"encoding\":\"base64\""));
+
+        let mut builder = CompressedPageBuilder::new_by_files();
+        assert_eq!(
+            2,
+            import_from_json_lines(exported.as_bytes(), &mut builder).unwrap()
+        );
+
+        let mut reader = CompressedPageReader::new(builder.get_payload().unwrap()).unwrap();
+
+        let msg = reader.get_next_message().unwrap().unwrap();
+        assert_eq!(1, msg.get_message_id().get_value());
+        assert_eq!(1_600_000_000_123_456, msg.get_created().unix_microseconds);
+        assert_eq!("application/json", msg.headers[0].value);
+
+        let msg = reader.get_next_message().unwrap().unwrap();
+        assert_eq!(vec![0xff, 0xfe, 0x00], msg.data);
+    }
+
+    #[test]
+    fn test_import_reports_line_number() {
+        let src =
+            "{\"id\":1,\"created\":\"2020-09-13T12:26:40Z\",\"data\":\"a\"}\n\n{\"id\":\"2\"}\n";
+
+        let mut builder = CompressedPageBuilder::new_as_single_file();
+
+        let result = import_from_json_lines(src.as_bytes(), &mut builder);
+
+        match result {
+            Err(PageImportError::InvalidRecord { line, .. }) => assert_eq!(3, line),
+            _ => panic!("Should not be here"),
+        }
+    }
+}
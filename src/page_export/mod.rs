@@ -0,0 +1,8 @@
+mod csv;
+mod error;
+mod exported_message;
+mod json_lines;
+pub use csv::*;
+pub use error::*;
+pub use exported_message::*;
+pub use json_lines::*;
@@ -0,0 +1,262 @@
+use my_service_bus_abstractions::{queue_with_intervals::QueueWithIntervals, MessageId};
+
+use super::{MessagesBucketId, PageLayout};
+
+// Inclusive range of page or sub page ids. Range is empty if from > to,
+// all the empty ranges are stored the same way so they compare equal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MessagesBucketIdRange<TId: MessagesBucketId> {
+    from: TId,
+    to: TId,
+}
+
+impl<TId: MessagesBucketId> MessagesBucketIdRange<TId> {
+    pub fn new(from: TId, to: TId) -> Self {
+        if from > to {
+            return Self::empty();
+        }
+
+        Self { from, to }
+    }
+
+    pub fn single(id: TId) -> Self {
+        Self::new(id, id)
+    }
+
+    pub fn empty() -> Self {
+        Self {
+            from: TId::new(0),
+            to: TId::new(-1),
+        }
+    }
+
+    pub fn from_message_ids(from: MessageId, to: MessageId) -> Self {
+        Self::from_message_ids_with_layout(from, to, PageLayout::DEFAULT)
+    }
+
+    pub fn from_message_ids_with_layout(
+        from: MessageId,
+        to: MessageId,
+        layout: PageLayout,
+    ) -> Self {
+        if from.get_value() > to.get_value() {
+            return Self::empty();
+        }
+
+        Self::new(
+            TId::from_message_id_with_layout(from, layout),
+            TId::from_message_id_with_layout(to, layout),
+        )
+    }
+
+    pub fn from_queue_with_intervals(src: &QueueWithIntervals) -> Self {
+        Self::from_queue_with_intervals_with_layout(src, PageLayout::DEFAULT)
+    }
+
+    // Intervals do not have to be sorted or disjoint - the range covers all of them
+    pub fn from_queue_with_intervals_with_layout(
+        src: &QueueWithIntervals,
+        layout: PageLayout,
+    ) -> Self {
+        let intervals = src.intervals.iter().filter(|itm| !itm.is_empty());
+
+        let from_id = intervals.clone().map(|itm| itm.from_id).min();
+        let to_id = intervals.map(|itm| itm.to_id).max();
+
+        match (from_id, to_id) {
+            (Some(from_id), Some(to_id)) => {
+                Self::from_message_ids_with_layout(from_id.into(), to_id.into(), layout)
+            }
+            _ => Self::empty(),
+        }
+    }
+
+    pub fn get_from(&self) -> TId {
+        self.from
+    }
+
+    pub fn get_to(&self) -> TId {
+        self.to
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.from > self.to
+    }
+
+    // Range of all the i64 values has 2^64 ids, so the length does not fit into u64 or usize
+    pub fn len(&self) -> u128 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        get_len(self.from.get_value(), self.to.get_value())
+    }
+
+    pub fn contains(&self, id: TId) -> bool {
+        id >= self.from && id <= self.to
+    }
+
+    pub fn intersect(&self, other: &Self) -> Self {
+        Self::new(self.from.max(other.from), self.to.min(other.to))
+    }
+
+    // Union exists only if ranges overlap or touch each other
+    pub fn union(&self, other: &Self) -> Option<Self> {
+        if self.is_empty() {
+            return Some(*other);
+        }
+
+        if other.is_empty() {
+            return Some(*self);
+        }
+
+        if self.from.get_value() > other.to.get_value().saturating_add(1)
+            || other.from.get_value() > self.to.get_value().saturating_add(1)
+        {
+            return None;
+        }
+
+        Some(Self::new(self.from.min(other.from), self.to.max(other.to)))
+    }
+
+    pub fn iter(&self) -> MessagesBucketIdRangeIterator<TId> {
+        let range = if self.is_empty() {
+            None
+        } else {
+            Some((self.from.get_value(), self.to.get_value()))
+        };
+
+        MessagesBucketIdRangeIterator {
+            range,
+            phantom: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<TId: MessagesBucketId> IntoIterator for MessagesBucketIdRange<TId> {
+    type Item = TId;
+
+    type IntoIter = MessagesBucketIdRangeIterator<TId>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+// Remaining ids are kept inclusive and None marks the end, so i64::MIN and i64::MAX are never stepped over
+pub struct MessagesBucketIdRangeIterator<TId: MessagesBucketId> {
+    range: Option<(i64, i64)>,
+    phantom: std::marker::PhantomData<TId>,
+}
+
+impl<TId: MessagesBucketId> Iterator for MessagesBucketIdRangeIterator<TId> {
+    type Item = TId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (from, to) = self.range?;
+
+        self.range = if from < to {
+            Some((from + 1, to))
+        } else {
+            None
+        };
+
+        Some(TId::new(from))
+    }
+
+    // Hint is exact unless the length does not fit into usize
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = match self.range {
+            Some((from, to)) => get_len(from, to),
+            None => 0,
+        };
+
+        match usize::try_from(len) {
+            Ok(len) => (len, Some(len)),
+            Err(_) => (usize::MAX, None),
+        }
+    }
+}
+
+impl<TId: MessagesBucketId> DoubleEndedIterator for MessagesBucketIdRangeIterator<TId> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let (from, to) = self.range?;
+
+        self.range = if from < to {
+            Some((from, to - 1))
+        } else {
+            None
+        };
+
+        Some(TId::new(to))
+    }
+}
+
+fn get_len(from: i64, to: i64) -> u128 {
+    u128::from(to.abs_diff(from)) + 1
+}
+
+// Test modules of page and sub page ranges build them from raw ids
+#[cfg(test)]
+impl<TId: MessagesBucketId> MessagesBucketIdRange<TId> {
+    pub(crate) fn from_values(from: i64, to: i64) -> Self {
+        Self::new(TId::new(from), TId::new(to))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::page_id::PageIdRange;
+
+    use super::*;
+
+    #[test]
+    fn test_empty_ranges_are_equal() {
+        assert_eq!(
+            PageIdRange::from_values(5, 4),
+            PageIdRange::from_values(1, 0)
+        );
+        assert_eq!(
+            MessagesBucketIdRange::empty(),
+            PageIdRange::from_values(1, 2).intersect(&PageIdRange::from_values(5, 8))
+        );
+        assert!(PageIdRange::from_values(5, 4).iter().next().is_none());
+    }
+
+    #[test]
+    fn test_ranges_at_i64_bounds() {
+        let result: Vec<i64> = PageIdRange::from_values(i64::MAX - 1, i64::MAX)
+            .iter()
+            .map(|itm| itm.get_value())
+            .collect();
+        assert_eq!(vec![i64::MAX - 1, i64::MAX], result);
+
+        let result: Vec<i64> = PageIdRange::from_values(i64::MIN, i64::MIN + 1)
+            .iter()
+            .rev()
+            .map(|itm| itm.get_value())
+            .collect();
+        assert_eq!(vec![i64::MIN + 1, i64::MIN], result);
+
+        let all = PageIdRange::from_values(i64::MIN, i64::MAX);
+        assert_eq!(1 << 64, all.len());
+        assert_eq!((usize::MAX, None), all.iter().size_hint());
+
+        let half = PageIdRange::from_values(0, i64::MAX);
+        assert_eq!(1 << 63, half.len());
+        assert_eq!(i64::MAX as u128 + 1, half.len());
+
+        assert_eq!(
+            Some(PageIdRange::from_values(0, i64::MAX)),
+            PageIdRange::from_values(0, 5).union(&PageIdRange::from_values(6, i64::MAX))
+        );
+        assert_eq!(
+            Some(PageIdRange::from_values(i64::MIN, 5)),
+            PageIdRange::from_values(i64::MIN, 0).union(&PageIdRange::from_values(0, 5))
+        );
+        assert_eq!(
+            None,
+            PageIdRange::from_values(i64::MAX, i64::MAX).union(&PageIdRange::from_values(0, 5))
+        );
+    }
+}
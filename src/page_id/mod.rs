@@ -1,10 +1,20 @@
+mod messages_bucket_id_range;
 mod page_id;
 mod page_id_iterator;
+mod page_id_name;
+mod page_id_range;
+mod page_layout;
 mod split_queue_with_intervals_by_pages;
 mod sub_pages_iterator;
+pub use messages_bucket_id_range::*;
 pub use page_id::*;
 
 pub use page_id_iterator::*;
+pub use page_id_name::*;
+pub use page_id_range::*;
+pub use page_layout::*;
+pub use split_queue_with_intervals_by_pages::MessagesBucketId;
+pub use split_queue_with_intervals_by_pages::SplitQueueWithIntervalsIterator;
 pub use split_queue_with_intervals_by_pages::SplittedByPageId;
 pub use split_queue_with_intervals_by_pages::SplittedByPageIdIterator;
 pub use sub_pages_iterator::*;
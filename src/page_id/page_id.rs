@@ -1,33 +1,171 @@
-use std::fmt::Display;
+use std::{fmt::Display, ops::RangeBounds};
 
 use my_service_bus_abstractions::MessageId;
 
 use crate::sub_page::SubPageId;
 
-use super::{PageIdIterator, SubPagesIterator};
+use super::{page_id_iterator::clamp_range, PageIdIterator, PageLayout, SubPagesIterator};
 
 pub const MESSAGES_IN_PAGE: i64 = 100_000;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidPageId {
+    NegativeMessageId(i64),
+    NegativePageId(i64),
+    PageIdIsOutOfRange { page_id: i64, max_page_id: i64 },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct PageId(i64);
 
 impl PageId {
     pub fn new(value: i64) -> Self {
         Self(value)
     }
+
+    pub fn try_new(value: i64) -> Result<Self, InvalidPageId> {
+        Self::try_new_with_layout(value, PageLayout::DEFAULT)
+    }
+
+    pub fn try_new_with_layout(value: i64, layout: PageLayout) -> Result<Self, InvalidPageId> {
+        if value < 0 {
+            return Err(InvalidPageId::NegativePageId(value));
+        }
+
+        let max_page_id = layout.get_max_page_id();
+
+        if value > max_page_id {
+            return Err(InvalidPageId::PageIdIsOutOfRange {
+                page_id: value,
+                max_page_id,
+            });
+        }
+
+        Ok(Self(value))
+    }
+
+    // Negative message ids are rounded down, so -1 goes to page -1 but not to page 0
     pub fn from_message_id(message_id: MessageId) -> Self {
-        let result = message_id.get_value() / MESSAGES_IN_PAGE;
-        Self(result.into())
+        Self::from_message_id_with_layout(message_id, PageLayout::DEFAULT)
+    }
+
+    pub fn from_message_id_with_layout(message_id: MessageId, layout: PageLayout) -> Self {
+        Self(
+            message_id
+                .get_value()
+                .div_euclid(layout.get_messages_in_page()),
+        )
+    }
+
+    pub fn try_from_message_id(message_id: MessageId) -> Result<Self, InvalidPageId> {
+        Self::try_from_message_id_with_layout(message_id, PageLayout::DEFAULT)
+    }
+
+    pub fn try_from_message_id_with_layout(
+        message_id: MessageId,
+        layout: PageLayout,
+    ) -> Result<Self, InvalidPageId> {
+        if message_id.get_value() < 0 {
+            return Err(InvalidPageId::NegativeMessageId(message_id.get_value()));
+        }
+
+        Ok(Self::from_message_id_with_layout(message_id, layout))
+    }
+
+    pub fn from_sub_page_id_with_layout(sub_page_id: SubPageId, layout: PageLayout) -> Self {
+        Self(
+            sub_page_id
+                .get_value()
+                .div_euclid(layout.get_sub_pages_per_page()),
+        )
     }
 
     pub fn get_first_message_id(&self) -> MessageId {
-        let result = self.0 * MESSAGES_IN_PAGE;
-        result.into()
+        self.get_first_message_id_with_layout(PageLayout::DEFAULT)
+    }
+
+    // Message id getters panic if the id does not fit into i64, which is never the case
+    // for page ids within 0..=max_page_id. Checked versions return None instead
+    pub fn get_first_message_id_with_layout(&self, layout: PageLayout) -> MessageId {
+        self.checked_get_first_message_id_with_layout(layout)
+            .expect("Page id is out of range of the layout")
+    }
+
+    pub fn checked_get_first_message_id(&self) -> Option<MessageId> {
+        self.checked_get_first_message_id_with_layout(PageLayout::DEFAULT)
+    }
+
+    pub fn checked_get_first_message_id_with_layout(
+        &self,
+        layout: PageLayout,
+    ) -> Option<MessageId> {
+        let result = self.0.checked_mul(layout.get_messages_in_page())?;
+        Some(result.into())
     }
 
     pub fn get_last_message_id(&self) -> MessageId {
-        let result = (self.0 + 1) * MESSAGES_IN_PAGE - 1;
-        result.into()
+        self.get_last_message_id_with_layout(PageLayout::DEFAULT)
+    }
+
+    pub fn get_last_message_id_with_layout(&self, layout: PageLayout) -> MessageId {
+        self.checked_get_last_message_id_with_layout(layout)
+            .expect("Page id is out of range of the layout")
+    }
+
+    pub fn checked_get_last_message_id(&self) -> Option<MessageId> {
+        self.checked_get_last_message_id_with_layout(PageLayout::DEFAULT)
+    }
+
+    pub fn checked_get_last_message_id_with_layout(&self, layout: PageLayout) -> Option<MessageId> {
+        let result = self
+            .0
+            .checked_mul(layout.get_messages_in_page())?
+            .checked_add(layout.get_messages_in_page() - 1)?;
+
+        Some(result.into())
+    }
+
+    // Checked and saturating arithmetic keeps page id within 0..=max_page_id of the layout
+    pub fn checked_add(&self, rhs: i64) -> Option<Self> {
+        self.checked_add_with_layout(rhs, PageLayout::DEFAULT)
+    }
+
+    pub fn checked_add_with_layout(&self, rhs: i64, layout: PageLayout) -> Option<Self> {
+        Self::try_new_with_layout(self.0.checked_add(rhs)?, layout).ok()
+    }
+
+    pub fn checked_sub(&self, rhs: i64) -> Option<Self> {
+        self.checked_sub_with_layout(rhs, PageLayout::DEFAULT)
+    }
+
+    pub fn checked_sub_with_layout(&self, rhs: i64, layout: PageLayout) -> Option<Self> {
+        Self::try_new_with_layout(self.0.checked_sub(rhs)?, layout).ok()
+    }
+
+    pub fn saturating_add(&self, rhs: i64) -> Self {
+        self.saturating_add_with_layout(rhs, PageLayout::DEFAULT)
+    }
+
+    pub fn saturating_add_with_layout(&self, rhs: i64, layout: PageLayout) -> Self {
+        Self(
+            self.0
+                .saturating_add(rhs)
+                .clamp(0, layout.get_max_page_id()),
+        )
+    }
+
+    pub fn saturating_sub(&self, rhs: i64) -> Self {
+        self.saturating_sub_with_layout(rhs, PageLayout::DEFAULT)
+    }
+
+    pub fn saturating_sub_with_layout(&self, rhs: i64, layout: PageLayout) -> Self {
+        Self(
+            self.0
+                .saturating_sub(rhs)
+                .clamp(0, layout.get_max_page_id()),
+        )
     }
 
     pub fn get_value(&self) -> i64 {
@@ -38,13 +176,51 @@ impl PageId {
         return PageIdIterator::new(*self);
     }
 
+    pub fn iterate_messages_with_layout(&self, layout: PageLayout) -> PageIdIterator {
+        PageIdIterator::new_with_layout(*self, layout)
+    }
+
+    pub fn iterate_messages_in(&self, range: impl RangeBounds<i64>) -> PageIdIterator {
+        PageIdIterator::new_in_range(*self, range)
+    }
+
+    pub fn iterate_messages_in_with_layout(
+        &self,
+        range: impl RangeBounds<i64>,
+        layout: PageLayout,
+    ) -> PageIdIterator {
+        PageIdIterator::new_in_range_with_layout(*self, range, layout)
+    }
+
     pub fn iterate_sub_page_ids(&self) -> SubPagesIterator {
-        let message_id = self.get_first_message_id();
-        let first_sub_page_id = SubPageId::from_message_id(message_id);
+        self.iterate_sub_page_ids_with_layout(PageLayout::DEFAULT)
+    }
+
+    pub fn iterate_sub_page_ids_with_layout(&self, layout: PageLayout) -> SubPagesIterator {
+        let first_sub_page_id = SubPageId::from_page_id_with_layout(*self, layout).get_value();
+
+        SubPagesIterator::new(
+            first_sub_page_id..=first_sub_page_id + (layout.get_sub_pages_per_page() - 1),
+        )
+    }
 
-        SubPagesIterator {
-            now_id: first_sub_page_id.get_value(),
-            to_id: first_sub_page_id.get_value() + crate::sub_page::SUB_PAGES_PER_PAGE,
+    pub fn iterate_sub_page_ids_in(&self, range: impl RangeBounds<i64>) -> SubPagesIterator {
+        self.iterate_sub_page_ids_in_with_layout(range, PageLayout::DEFAULT)
+    }
+
+    // Iterates only sub page ids of the range which belong to the page
+    pub fn iterate_sub_page_ids_in_with_layout(
+        &self,
+        range: impl RangeBounds<i64>,
+        layout: PageLayout,
+    ) -> SubPagesIterator {
+        let first_sub_page_id = SubPageId::from_page_id_with_layout(*self, layout).get_value();
+
+        let last_sub_page_id = first_sub_page_id + (layout.get_sub_pages_per_page() - 1);
+
+        match clamp_range(range, first_sub_page_id, last_sub_page_id) {
+            Some((from_id, to_id)) => SubPagesIterator::new(from_id..=to_id),
+            None => SubPagesIterator::empty(),
         }
     }
 }
@@ -67,11 +243,17 @@ impl AsRef<i64> for PageId {
     }
 }
 
+// Operators do not check the layout range but panic on i64 overflow in release builds as well.
+// Use checked or saturating arithmetic for ids which come from the wire
 impl std::ops::Sub<i64> for PageId {
     type Output = Self;
 
     fn sub(self, rhs: i64) -> Self::Output {
-        Self(self.0 - rhs)
+        Self(
+            self.0
+                .checked_sub(rhs)
+                .expect("PageId subtraction overflow"),
+        )
     }
 }
 
@@ -79,7 +261,7 @@ impl std::ops::Add<i64> for PageId {
     type Output = Self;
 
     fn add(self, rhs: i64) -> Self::Output {
-        Self(self.0 + rhs)
+        Self(self.0.checked_add(rhs).expect("PageId addition overflow"))
     }
 }
 
@@ -123,4 +305,137 @@ mod tests {
 
         assert_eq!(299_999, PageId(2).get_last_message_id().get_value());
     }
+
+    #[test]
+    fn test_negative_message_ids() {
+        assert_eq!(-1, PageId::from_message_id((-1).into()).get_value());
+        assert_eq!(-1, PageId::from_message_id((-100_000).into()).get_value());
+        assert_eq!(-2, PageId::from_message_id((-100_001).into()).get_value());
+
+        assert_eq!(
+            Err(InvalidPageId::NegativeMessageId(-1)),
+            PageId::try_from_message_id((-1).into())
+        );
+        assert_eq!(Ok(PageId(1)), PageId::try_from_message_id(100_000.into()));
+    }
+
+    #[test]
+    fn test_try_new() {
+        let max_page_id = PageLayout::DEFAULT.get_max_page_id();
+
+        assert_eq!(Ok(PageId(0)), PageId::try_new(0));
+        assert_eq!(Ok(PageId(max_page_id)), PageId::try_new(max_page_id));
+        assert_eq!(Err(InvalidPageId::NegativePageId(-5)), PageId::try_new(-5));
+        assert_eq!(
+            Err(InvalidPageId::PageIdIsOutOfRange {
+                page_id: max_page_id + 1,
+                max_page_id
+            }),
+            PageId::try_new(max_page_id + 1)
+        );
+
+        assert!(PageId(max_page_id).checked_get_last_message_id().is_some());
+        assert!(PageId(max_page_id + 1)
+            .checked_get_last_message_id()
+            .is_none());
+        assert!(PageId(i64::MAX / 100_000 + 1)
+            .checked_get_first_message_id()
+            .is_none());
+    }
+
+    #[test]
+    fn test_checked_and_saturating_arithmetic() {
+        let max_page_id = PageLayout::DEFAULT.get_max_page_id();
+
+        assert_eq!(Some(PageId(3)), PageId(1).checked_add(2));
+        assert_eq!(None, PageId(max_page_id).checked_add(1));
+        assert_eq!(None, PageId(1).checked_add(i64::MAX));
+
+        assert_eq!(Some(PageId(0)), PageId(1).checked_sub(1));
+        assert_eq!(None, PageId(1).checked_sub(2));
+        assert_eq!(None, PageId(1).checked_sub(i64::MIN));
+
+        assert_eq!(PageId(max_page_id), PageId(1).saturating_add(i64::MAX));
+        assert_eq!(PageId(0), PageId(1).saturating_sub(5));
+        assert_eq!(PageId(4), PageId(5).saturating_sub(1));
+    }
+
+    #[test]
+    fn test_checked_and_saturating_arithmetic_with_layout() {
+        let layout = PageLayout::new(1_000, 100).unwrap();
+        let max_page_id = layout.get_max_page_id();
+
+        assert!(max_page_id > PageLayout::DEFAULT.get_max_page_id());
+
+        assert_eq!(
+            Some(PageId(max_page_id)),
+            PageId(max_page_id - 1).checked_add_with_layout(1, layout)
+        );
+        assert_eq!(None, PageId(max_page_id).checked_add_with_layout(1, layout));
+        assert_eq!(None, PageId(0).checked_sub_with_layout(1, layout));
+
+        assert_eq!(
+            PageId(max_page_id),
+            PageId(1).saturating_add_with_layout(i64::MAX, layout)
+        );
+        assert_eq!(PageId(0), PageId(1).saturating_sub_with_layout(5, layout));
+    }
+
+    #[test]
+    fn test_ops() {
+        assert_eq!(PageId(3), PageId(1) + 2);
+        assert_eq!(PageId(-1), PageId(1) - 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "PageId addition overflow")]
+    fn test_add_overflow_panics() {
+        let _ = PageId(i64::MAX) + 1;
+    }
+
+    #[test]
+    #[should_panic(expected = "Page id is out of range of the layout")]
+    fn test_last_message_id_overflow_panics() {
+        PageId(PageLayout::DEFAULT.get_max_page_id() + 1).get_last_message_id();
+    }
+
+    #[test]
+    fn test_custom_layout() {
+        let layout = PageLayout::new(1_000, 100).unwrap();
+
+        let page_id = PageId::from_message_id_with_layout(2_500.into(), layout);
+        assert_eq!(2, page_id.get_value());
+
+        assert_eq!(
+            2_000,
+            page_id.get_first_message_id_with_layout(layout).get_value()
+        );
+        assert_eq!(
+            2_999,
+            page_id.get_last_message_id_with_layout(layout).get_value()
+        );
+
+        assert_eq!(1_000, page_id.iterate_messages_with_layout(layout).count());
+
+        let sub_page_ids: Vec<i64> = page_id
+            .iterate_sub_page_ids_with_layout(layout)
+            .map(|itm| itm.get_value())
+            .collect();
+
+        assert_eq!((20..30).collect::<Vec<_>>(), sub_page_ids);
+
+        assert_eq!(
+            page_id,
+            PageId::from_sub_page_id_with_layout(SubPageId::new(29), layout)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_as_plain_number() {
+        assert_eq!("15", serde_json::to_string(&PageId::new(15)).unwrap());
+
+        let page_id: PageId = serde_json::from_str("15").unwrap();
+        assert_eq!(15, page_id.get_value());
+    }
 }
@@ -1,32 +1,184 @@
+use std::ops::{Bound, RangeBounds, RangeInclusive};
+
 use my_service_bus_abstractions::MessageId;
 
-use super::{PageId, MESSAGES_IN_PAGE};
+use super::{PageId, PageLayout};
 
+// Last id is kept inclusive, so the last page of a layout may end at i64::MAX
 pub struct PageIdIterator {
-    from_id: i64,
-    to_id: i64,
+    ids: RangeInclusive<i64>,
 }
 
 impl PageIdIterator {
     pub fn new(page_id: PageId) -> Self {
-        let from_id = page_id.get_first_message_id().get_value();
+        Self::new_with_layout(page_id, PageLayout::DEFAULT)
+    }
+
+    pub fn new_with_layout(page_id: PageId, layout: PageLayout) -> Self {
         Self {
-            from_id,
-            to_id: from_id + MESSAGES_IN_PAGE,
+            ids: page_id.get_first_message_id_with_layout(layout).get_value()
+                ..=page_id.get_last_message_id_with_layout(layout).get_value(),
         }
     }
+
+    // Iterates only message ids of the range which belong to the page
+    pub fn new_in_range(page_id: PageId, range: impl RangeBounds<i64>) -> Self {
+        Self::new_in_range_with_layout(page_id, range, PageLayout::DEFAULT)
+    }
+
+    pub fn new_in_range_with_layout(
+        page_id: PageId,
+        range: impl RangeBounds<i64>,
+        layout: PageLayout,
+    ) -> Self {
+        let page = Self::new_with_layout(page_id, layout);
+
+        let ids = match clamp_range(range, *page.ids.start(), *page.ids.end()) {
+            Some((from_id, to_id)) => from_id..=to_id,
+            None => {
+                // Exhausted range yields nothing from both ends
+                let mut ids = 0..=0;
+                ids.next();
+                ids
+            }
+        };
+
+        Self { ids }
+    }
 }
 
 impl Iterator for PageIdIterator {
     type Item = MessageId;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.from_id >= self.to_id {
-            None
-        } else {
-            let result = self.from_id;
-            self.from_id = self.from_id + 1;
-            Some(result.into())
-        }
+        self.ids.next().map(|itm| itm.into())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+impl DoubleEndedIterator for PageIdIterator {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.ids.next_back().map(|itm| itm.into())
+    }
+}
+
+impl ExactSizeIterator for PageIdIterator {}
+
+// Returns inclusive [from, to] part of the range which fits into [first, last], or None if nothing fits
+pub(crate) fn clamp_range(
+    range: impl RangeBounds<i64>,
+    first: i64,
+    last: i64,
+) -> Option<(i64, i64)> {
+    let from = match range.start_bound() {
+        Bound::Included(value) => *value,
+        Bound::Excluded(value) => value.checked_add(1)?,
+        Bound::Unbounded => first,
+    };
+
+    let to = match range.end_bound() {
+        Bound::Included(value) => *value,
+        Bound::Excluded(value) => value.checked_sub(1)?,
+        Bound::Unbounded => last,
+    };
+
+    let from = from.max(first);
+    let to = to.min(last);
+
+    if from > to {
+        return None;
+    }
+
+    Some((from, to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_vec(src: impl Iterator<Item = MessageId>) -> Vec<i64> {
+        src.map(|itm| itm.get_value()).collect()
+    }
+
+    #[test]
+    fn test_exact_size_and_reverse() {
+        let mut iterator = PageId::new(1).iterate_messages();
+
+        assert_eq!(100_000, iterator.len());
+        assert_eq!(199_999, iterator.next_back().unwrap().get_value());
+        assert_eq!(100_000, iterator.next().unwrap().get_value());
+        assert_eq!(99_998, iterator.len());
+
+        let last_three = to_vec(PageId::new(0).iterate_messages().rev().take(3));
+        assert_eq!(vec![99_999, 99_998, 99_997], last_three);
+    }
+
+    #[test]
+    fn test_iterate_messages_in_range() {
+        let page_id = PageId::new(1);
+
+        assert_eq!(
+            vec![100_005, 100_006, 100_007],
+            to_vec(page_id.iterate_messages_in(100_005..100_008))
+        );
+
+        assert_eq!(
+            vec![199_998, 199_999],
+            to_vec(page_id.iterate_messages_in(199_998..=250_000))
+        );
+
+        assert_eq!(
+            vec![100_000, 100_001],
+            to_vec(page_id.iterate_messages_in(..=100_001))
+        );
+
+        assert_eq!(100_000, page_id.iterate_messages_in(..).len());
+        assert_eq!(0, page_id.iterate_messages_in(0..10).len());
+        assert_eq!(0, page_id.iterate_messages_in(100_010..100_005).len());
+    }
+
+    #[test]
+    fn test_clamp_range() {
+        assert_eq!(Some((5, 10)), clamp_range(0..100, 5, 10));
+        assert_eq!(Some((7, 7)), clamp_range(7..=7, 5, 10));
+        assert_eq!(None, clamp_range(20..30, 5, 10));
+        assert_eq!(Some((5, 10)), clamp_range(i64::MIN..=i64::MAX, 5, 10));
+        assert_eq!(
+            Some((i64::MAX, i64::MAX)),
+            clamp_range(i64::MAX.., 0, i64::MAX)
+        );
+        assert_eq!(
+            None,
+            clamp_range((Bound::Excluded(i64::MAX), Bound::Unbounded), 0, i64::MAX)
+        );
+        assert_eq!(None, clamp_range(..i64::MIN, i64::MIN, 0));
+    }
+
+    #[test]
+    fn test_last_page_of_power_of_two_layout() {
+        let layout = PageLayout::new(1024, 1).unwrap();
+        let page_id = PageId::new(layout.get_max_page_id());
+
+        let mut iterator = page_id.iterate_messages_with_layout(layout);
+
+        assert_eq!(1024, iterator.len());
+        assert_eq!(i64::MAX, iterator.next_back().unwrap().get_value());
+        assert_eq!(i64::MAX - 1023, iterator.next().unwrap().get_value());
+        assert_eq!(1022, iterator.len());
+        assert_eq!(1022, iterator.count());
+
+        assert_eq!(
+            vec![i64::MAX - 1, i64::MAX],
+            to_vec(page_id.iterate_messages_in_with_layout(i64::MAX - 1.., layout))
+        );
+        assert_eq!(
+            0,
+            page_id
+                .iterate_messages_in_with_layout(..i64::MAX - 1023, layout)
+                .len()
+        );
     }
 }
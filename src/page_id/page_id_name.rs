@@ -0,0 +1,219 @@
+use std::str::FromStr;
+
+use super::PageId;
+
+// Width of i64::MAX, so every non negative id has the same length and names are sorted as numbers
+pub const CANONICAL_ID_WIDTH: usize = 19;
+
+pub const PAGE_FILE_EXTENSION: &str = "page";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidIdName {
+    WrongLength {
+        expected: usize,
+        actual: usize,
+    },
+    NotADigit {
+        position: usize,
+        symbol: char,
+    },
+    WrongExtension {
+        expected: &'static str,
+        actual: String,
+    },
+    IsOutOfRange(String),
+}
+
+pub(crate) fn format_canonical_id(value: i64) -> String {
+    format!("{:0width$}", value, width = CANONICAL_ID_WIDTH)
+}
+
+pub(crate) fn parse_canonical_id(src: &str) -> Result<i64, InvalidIdName> {
+    if let Some((position, symbol)) = src.chars().enumerate().find(|(_, c)| !c.is_ascii_digit()) {
+        return Err(InvalidIdName::NotADigit { position, symbol });
+    }
+
+    if src.len() != CANONICAL_ID_WIDTH {
+        return Err(InvalidIdName::WrongLength {
+            expected: CANONICAL_ID_WIDTH,
+            actual: src.len(),
+        });
+    }
+
+    src.parse()
+        .map_err(|_| InvalidIdName::IsOutOfRange(src.to_string()))
+}
+
+pub(crate) fn split_file_name<'s>(
+    src: &'s str,
+    extension: &'static str,
+) -> Result<&'s str, InvalidIdName> {
+    let (name, actual) = src.rsplit_once('.').unwrap_or((src, ""));
+
+    if actual != extension {
+        return Err(InvalidIdName::WrongExtension {
+            expected: extension,
+            actual: actual.to_string(),
+        });
+    }
+
+    Ok(name)
+}
+
+impl PageId {
+    // Canonical form is defined for 0..=max_page_id of the default layout only. Negative ids and ids
+    // above the max are still formatted, but FromStr rejects such names
+    pub fn to_canonical_string(&self) -> String {
+        format_canonical_id(self.get_value())
+    }
+
+    pub fn to_file_name(&self) -> String {
+        format!("{}.{}", self.to_canonical_string(), PAGE_FILE_EXTENSION)
+    }
+
+    pub fn from_file_name(src: &str) -> Result<Self, InvalidIdName> {
+        split_file_name(src, PAGE_FILE_EXTENSION)?.parse()
+    }
+}
+
+impl FromStr for PageId {
+    type Err = InvalidIdName;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let value = parse_canonical_id(src)?;
+        PageId::try_new(value).map_err(|_| InvalidIdName::IsOutOfRange(src.to_string()))
+    }
+}
+
+impl<'s> TryFrom<&'s str> for PageId {
+    type Error = InvalidIdName;
+
+    fn try_from(src: &'s str) -> Result<Self, Self::Error> {
+        src.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::page_id::PageLayout;
+
+    use super::*;
+
+    #[test]
+    fn test_canonical_string() {
+        assert_eq!(
+            "0000000000000000123",
+            PageId::new(123).to_canonical_string()
+        );
+        assert_eq!("0000000000000000123.page", PageId::new(123).to_file_name());
+
+        assert_eq!(
+            PageId::new(123),
+            "0000000000000000123".parse::<PageId>().unwrap()
+        );
+        assert_eq!(
+            PageId::new(123),
+            PageId::from_file_name("0000000000000000123.page").unwrap()
+        );
+        assert_eq!(
+            PageId::new(0),
+            PageId::try_from("0000000000000000000").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_round_trip_at_the_edges_of_the_range() {
+        let max_page_id = PageLayout::DEFAULT.get_max_page_id();
+
+        for id in [0, max_page_id] {
+            let page_id = PageId::new(id);
+            assert_eq!(Ok(page_id), PageId::from_file_name(&page_id.to_file_name()));
+        }
+
+        let above_max = PageId::new(max_page_id + 1).to_canonical_string();
+        assert_eq!(CANONICAL_ID_WIDTH, above_max.len());
+        assert_eq!(
+            Err(InvalidIdName::IsOutOfRange(above_max.clone())),
+            above_max.parse::<PageId>()
+        );
+
+        let negative = PageId::new(-5).to_canonical_string();
+        assert_eq!("-000000000000000005", negative);
+        assert_eq!(
+            Err(InvalidIdName::NotADigit {
+                position: 0,
+                symbol: '-'
+            }),
+            negative.parse::<PageId>()
+        );
+    }
+
+    #[test]
+    fn test_names_are_sorted_as_ids() {
+        let ids = [0, 9, 10, 99, 100, 12_345, 99_999_999];
+
+        let mut names: Vec<String> = ids
+            .iter()
+            .rev()
+            .map(|itm| PageId::new(*itm).to_file_name())
+            .collect();
+
+        names.sort();
+
+        let result: Vec<i64> = names
+            .iter()
+            .map(|itm| PageId::from_file_name(itm).unwrap().get_value())
+            .collect();
+
+        assert_eq!(ids.to_vec(), result);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Err(InvalidIdName::WrongLength {
+                expected: CANONICAL_ID_WIDTH,
+                actual: 3
+            }),
+            "123".parse::<PageId>()
+        );
+
+        assert_eq!(
+            Err(InvalidIdName::NotADigit {
+                position: 0,
+                symbol: '-'
+            }),
+            "-000000000000000123".parse::<PageId>()
+        );
+
+        assert_eq!(
+            Err(InvalidIdName::IsOutOfRange(
+                "9999999999999999999".to_string()
+            )),
+            "9999999999999999999".parse::<PageId>()
+        );
+
+        assert_eq!(
+            Err(InvalidIdName::IsOutOfRange(
+                "0999999999999999999".to_string()
+            )),
+            "0999999999999999999".parse::<PageId>()
+        );
+
+        assert_eq!(
+            Err(InvalidIdName::WrongExtension {
+                expected: PAGE_FILE_EXTENSION,
+                actual: "zip".to_string()
+            }),
+            PageId::from_file_name("0000000000000000123.zip")
+        );
+
+        assert_eq!(
+            Err(InvalidIdName::WrongExtension {
+                expected: PAGE_FILE_EXTENSION,
+                actual: "".to_string()
+            }),
+            PageId::from_file_name("0000000000000000123")
+        );
+    }
+}
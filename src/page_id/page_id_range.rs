@@ -0,0 +1,140 @@
+use crate::sub_page::SubPageIdRange;
+
+use super::{MessagesBucketIdRange, MessagesBucketIdRangeIterator, PageId, PageLayout};
+
+pub type PageIdRange = MessagesBucketIdRange<PageId>;
+
+pub type PageIdRangeIterator = MessagesBucketIdRangeIterator<PageId>;
+
+impl MessagesBucketIdRange<PageId> {
+    pub fn get_sub_page_id_range(&self) -> SubPageIdRange {
+        self.get_sub_page_id_range_with_layout(PageLayout::DEFAULT)
+    }
+
+    pub fn get_sub_page_id_range_with_layout(&self, layout: PageLayout) -> SubPageIdRange {
+        if self.is_empty() {
+            return SubPageIdRange::empty();
+        }
+
+        SubPageIdRange::from_message_ids_with_layout(
+            self.get_from().get_first_message_id_with_layout(layout),
+            self.get_to().get_last_message_id_with_layout(layout),
+            layout,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus_abstractions::queue_with_intervals::{QueueIndexRange, QueueWithIntervals};
+
+    use super::*;
+
+    fn to_vec(src: impl Iterator<Item = PageId>) -> Vec<i64> {
+        src.map(|itm| itm.get_value()).collect()
+    }
+
+    #[test]
+    fn test_from_message_ids() {
+        let result = PageIdRange::from_message_ids(99_999.into(), 200_000.into());
+
+        assert_eq!(PageIdRange::from_values(0, 2), result);
+        assert_eq!(3, result.len());
+        assert!(result.contains(PageId::new(1)));
+        assert!(!result.contains(PageId::new(3)));
+
+        assert!(PageIdRange::from_message_ids(10.into(), 9.into()).is_empty());
+    }
+
+    #[test]
+    fn test_from_queue_with_intervals() {
+        let mut src = QueueWithIntervals::from_single_interval(5, 10);
+        src.intervals.push(QueueIndexRange {
+            from_id: 300_000,
+            to_id: 300_001,
+        });
+
+        assert_eq!(
+            PageIdRange::from_values(0, 3),
+            PageIdRange::from_queue_with_intervals(&src)
+        );
+
+        assert!(PageIdRange::from_queue_with_intervals(&QueueWithIntervals::new()).is_empty());
+    }
+
+    #[test]
+    fn test_from_unsorted_queue_with_intervals() {
+        let mut src = QueueWithIntervals::new();
+
+        for (from_id, to_id) in [(300_000, 300_001), (5, 10), (150_000, 450_000), (7, 6)] {
+            src.intervals.push(QueueIndexRange { from_id, to_id });
+        }
+
+        assert_eq!(
+            PageIdRange::from_values(0, 4),
+            PageIdRange::from_queue_with_intervals(&src)
+        );
+    }
+
+    #[test]
+    fn test_intersect() {
+        assert_eq!(
+            PageIdRange::from_values(3, 5),
+            PageIdRange::from_values(1, 5).intersect(&PageIdRange::from_values(3, 8))
+        );
+        assert_eq!(
+            PageIdRange::from_values(4, 4),
+            PageIdRange::from_values(4, 4).intersect(&PageIdRange::from_values(0, 8))
+        );
+        assert!(PageIdRange::from_values(1, 2)
+            .intersect(&PageIdRange::from_values(3, 4))
+            .is_empty());
+    }
+
+    #[test]
+    fn test_union() {
+        assert_eq!(
+            Some(PageIdRange::from_values(1, 8)),
+            PageIdRange::from_values(1, 5).union(&PageIdRange::from_values(3, 8))
+        );
+        assert_eq!(
+            Some(PageIdRange::from_values(1, 4)),
+            PageIdRange::from_values(1, 2).union(&PageIdRange::from_values(3, 4))
+        );
+        assert_eq!(
+            None,
+            PageIdRange::from_values(1, 2).union(&PageIdRange::from_values(4, 5))
+        );
+        assert_eq!(
+            Some(PageIdRange::from_values(4, 5)),
+            PageIdRange::from_values(1, 0).union(&PageIdRange::from_values(4, 5))
+        );
+    }
+
+    #[test]
+    fn test_iteration_in_both_directions() {
+        assert_eq!(vec![2, 3, 4], to_vec(PageIdRange::from_values(2, 4).iter()));
+        assert_eq!(
+            vec![4, 3, 2],
+            to_vec(PageIdRange::from_values(2, 4).iter().rev())
+        );
+
+        let mut iterator = PageIdRange::from_values(2, 4).iter();
+        assert_eq!((3, Some(3)), iterator.size_hint());
+        assert_eq!(2, iterator.next().unwrap().get_value());
+        assert_eq!(4, iterator.next_back().unwrap().get_value());
+        assert_eq!((1, Some(1)), iterator.size_hint());
+        assert_eq!(3, iterator.next().unwrap().get_value());
+        assert!(iterator.next_back().is_none());
+
+        assert_eq!(0, PageIdRange::from_values(1, 0).iter().count());
+    }
+
+    #[test]
+    fn test_sub_page_id_range() {
+        let result = PageIdRange::from_values(1, 2).get_sub_page_id_range();
+
+        assert_eq!(100, result.get_from().get_value());
+        assert_eq!(299, result.get_to().get_value());
+    }
+}
@@ -0,0 +1,159 @@
+use crate::sub_page::SUB_PAGE_MESSAGES_AMOUNT;
+
+use super::MESSAGES_IN_PAGE;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidPageLayout {
+    MessagesInPageIsNotPositive(i64),
+    MessagesInSubPageIsNotPositive(i64),
+    PageIsNotDivisibleBySubPages {
+        messages_in_page: i64,
+        messages_in_sub_page: i64,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "PageLayoutModel"))]
+pub struct PageLayout {
+    messages_in_page: i64,
+    messages_in_sub_page: i64,
+}
+
+impl PageLayout {
+    pub const DEFAULT: PageLayout = PageLayout {
+        messages_in_page: MESSAGES_IN_PAGE,
+        messages_in_sub_page: SUB_PAGE_MESSAGES_AMOUNT,
+    };
+
+    pub fn new(
+        messages_in_page: i64,
+        messages_in_sub_page: i64,
+    ) -> Result<Self, InvalidPageLayout> {
+        if messages_in_page <= 0 {
+            return Err(InvalidPageLayout::MessagesInPageIsNotPositive(
+                messages_in_page,
+            ));
+        }
+
+        if messages_in_sub_page <= 0 {
+            return Err(InvalidPageLayout::MessagesInSubPageIsNotPositive(
+                messages_in_sub_page,
+            ));
+        }
+
+        if messages_in_page % messages_in_sub_page != 0 {
+            return Err(InvalidPageLayout::PageIsNotDivisibleBySubPages {
+                messages_in_page,
+                messages_in_sub_page,
+            });
+        }
+
+        Ok(Self {
+            messages_in_page,
+            messages_in_sub_page,
+        })
+    }
+
+    pub fn get_messages_in_page(&self) -> i64 {
+        self.messages_in_page
+    }
+
+    pub fn get_messages_in_sub_page(&self) -> i64 {
+        self.messages_in_sub_page
+    }
+
+    pub fn get_sub_pages_per_page(&self) -> i64 {
+        self.messages_in_page / self.messages_in_sub_page
+    }
+
+    // Biggest page id which last message id still fits into i64
+    pub fn get_max_page_id(&self) -> i64 {
+        (i64::MAX - self.messages_in_page + 1) / self.messages_in_page
+    }
+
+    // Last sub page of the max page, so the page of every valid sub page id is valid as well
+    pub fn get_max_sub_page_id(&self) -> i64 {
+        self.get_max_page_id() * self.get_sub_pages_per_page() + (self.get_sub_pages_per_page() - 1)
+    }
+}
+
+impl Default for PageLayout {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
+}
+
+// Deserialized layout goes through the same validation as PageLayout::new
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct PageLayoutModel {
+    messages_in_page: i64,
+    messages_in_sub_page: i64,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<PageLayoutModel> for PageLayout {
+    type Error = String;
+
+    fn try_from(src: PageLayoutModel) -> Result<Self, Self::Error> {
+        PageLayout::new(src.messages_in_page, src.messages_in_sub_page)
+            .map_err(|err| format!("{:?}", err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sub_page::SUB_PAGES_PER_PAGE;
+
+    #[test]
+    fn test_default_layout() {
+        let layout = PageLayout::default();
+
+        assert_eq!(MESSAGES_IN_PAGE, layout.get_messages_in_page());
+        assert_eq!(SUB_PAGE_MESSAGES_AMOUNT, layout.get_messages_in_sub_page());
+        assert_eq!(SUB_PAGES_PER_PAGE, layout.get_sub_pages_per_page());
+    }
+
+    #[test]
+    fn test_custom_layout() {
+        let layout = PageLayout::new(1_000_000, 5_000).unwrap();
+        assert_eq!(200, layout.get_sub_pages_per_page());
+    }
+
+    #[test]
+    fn test_max_ids() {
+        let layout = PageLayout::default();
+
+        let last_message_id = (layout.get_max_page_id() + 1) * MESSAGES_IN_PAGE - 1;
+        assert!(i64::MAX - last_message_id < MESSAGES_IN_PAGE);
+
+        let last_message_id = (layout.get_max_sub_page_id() + 1) * SUB_PAGE_MESSAGES_AMOUNT - 1;
+        assert_eq!(
+            (layout.get_max_page_id() + 1) * MESSAGES_IN_PAGE - 1,
+            last_message_id
+        );
+    }
+
+    #[test]
+    fn test_invalid_layouts() {
+        assert_eq!(
+            Err(InvalidPageLayout::MessagesInPageIsNotPositive(0)),
+            PageLayout::new(0, 10)
+        );
+
+        assert_eq!(
+            Err(InvalidPageLayout::MessagesInSubPageIsNotPositive(-1)),
+            PageLayout::new(100, -1)
+        );
+
+        assert_eq!(
+            Err(InvalidPageLayout::PageIsNotDivisibleBySubPages {
+                messages_in_page: 100,
+                messages_in_sub_page: 30
+            }),
+            PageLayout::new(100, 30)
+        );
+    }
+}
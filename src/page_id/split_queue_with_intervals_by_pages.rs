@@ -1,28 +1,60 @@
-use my_service_bus_abstractions::queue_with_intervals::{QueueIndexRange, QueueWithIntervals};
+use my_service_bus_abstractions::{
+    queue_with_intervals::{QueueIndexRange, QueueWithIntervals},
+    MessageId,
+};
+
+use super::{PageId, PageLayout};
+
+// Id of the continuous block of messages queue can be split by
+pub trait MessagesBucketId: Copy + Ord {
+    fn new(value: i64) -> Self;
+    fn get_value(&self) -> i64;
+    fn from_message_id_with_layout(message_id: MessageId, layout: PageLayout) -> Self;
+    fn get_last_message_id_with_layout(&self, layout: PageLayout) -> MessageId;
+}
 
-use super::PageId;
+impl MessagesBucketId for PageId {
+    fn new(value: i64) -> Self {
+        PageId::new(value)
+    }
 
-pub struct SplittedByPageId {
-    pub page_id: PageId,
-    pub ids: QueueWithIntervals,
+    fn get_value(&self) -> i64 {
+        PageId::get_value(self)
+    }
+
+    fn from_message_id_with_layout(message_id: MessageId, layout: PageLayout) -> Self {
+        PageId::from_message_id_with_layout(message_id, layout)
+    }
+
+    fn get_last_message_id_with_layout(&self, layout: PageLayout) -> MessageId {
+        PageId::get_last_message_id_with_layout(self, layout)
+    }
 }
 
-pub struct SplittedByPageIdIterator {
+pub struct SplitQueueWithIntervalsIterator<TId: MessagesBucketId> {
     intervals: Vec<QueueIndexRange>,
     index: usize,
+    layout: PageLayout,
+    phantom: std::marker::PhantomData<TId>,
 }
 
-impl SplittedByPageIdIterator {
+impl<TId: MessagesBucketId> SplitQueueWithIntervalsIterator<TId> {
     pub fn new(src: &QueueWithIntervals) -> Self {
+        Self::new_with_layout(src, PageLayout::DEFAULT)
+    }
+
+    pub fn new_with_layout(src: &QueueWithIntervals, layout: PageLayout) -> Self {
         Self {
             intervals: src.intervals.clone(),
             index: 0,
+            layout,
+            phantom: std::marker::PhantomData,
         }
     }
 }
 
-impl Iterator for SplittedByPageIdIterator {
-    type Item = SplittedByPageId;
+impl<TId: MessagesBucketId> Iterator for SplitQueueWithIntervalsIterator<TId> {
+    type Item = (TId, QueueWithIntervals);
 
     fn next(&mut self) -> Option<Self::Item> {
         let el = self.intervals.get_mut(self.index)?;
@@ -32,25 +64,28 @@ impl Iterator for SplittedByPageIdIterator {
         }
 
         let mut ids = QueueWithIntervals::new();
-        let mut page_id = None;
+        let mut id = None;
 
         while let Some(el) = self.intervals.get_mut(self.index) {
-            if page_id.is_none() {
-                page_id = Some(PageId::from_message_id(el.from_id.into()));
+            if id.is_none() {
+                id = Some(TId::from_message_id_with_layout(
+                    el.from_id.into(),
+                    self.layout,
+                ));
             }
 
-            let page_id = page_id.unwrap();
+            let id = id.unwrap();
 
-            let from_page_id = PageId::from_message_id(el.from_id.into());
+            let from_id = TId::from_message_id_with_layout(el.from_id.into(), self.layout);
 
-            if from_page_id.get_value() > page_id.get_value() {
-                return Some(SplittedByPageId { page_id, ids });
+            if from_id > id {
+                return Some((id, ids));
             }
 
-            let to_page_id = PageId::from_message_id(el.to_id.into());
+            let to_id = TId::from_message_id_with_layout(el.to_id.into(), self.layout);
 
-            if to_page_id.get_value() > page_id.get_value() {
-                let to_id = page_id.get_last_message_id();
+            if to_id > id {
+                let to_id = id.get_last_message_id_with_layout(self.layout);
 
                 ids.intervals.push(QueueIndexRange {
                     from_id: el.from_id,
@@ -59,7 +94,7 @@ impl Iterator for SplittedByPageIdIterator {
 
                 el.from_id = to_id.get_value() + 1;
 
-                return Some(SplittedByPageId { page_id, ids });
+                return Some((id, ids));
             }
 
             ids.intervals.push(QueueIndexRange {
@@ -70,10 +105,37 @@ impl Iterator for SplittedByPageIdIterator {
             self.index += 1;
         }
 
-        return Some(SplittedByPageId {
-            page_id: page_id.unwrap(),
-            ids,
-        });
+        Some((id.unwrap(), ids))
+    }
+}
+
+pub struct SplittedByPageId {
+    pub page_id: PageId,
+    pub ids: QueueWithIntervals,
+}
+
+pub struct SplittedByPageIdIterator {
+    inner: SplitQueueWithIntervalsIterator<PageId>,
+}
+
+impl SplittedByPageIdIterator {
+    pub fn new(src: &QueueWithIntervals) -> Self {
+        Self::new_with_layout(src, PageLayout::DEFAULT)
+    }
+
+    pub fn new_with_layout(src: &QueueWithIntervals, layout: PageLayout) -> Self {
+        Self {
+            inner: SplitQueueWithIntervalsIterator::new_with_layout(src, layout),
+        }
+    }
+}
+
+impl Iterator for SplittedByPageIdIterator {
+    type Item = SplittedByPageId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (page_id, ids) = self.inner.next()?;
+        Some(SplittedByPageId { page_id, ids })
     }
 }
 
@@ -147,4 +209,27 @@ mod tests {
         assert_eq!(200_000, result[2].ids.intervals[0].from_id);
         assert_eq!(200_020, result[2].ids.intervals[0].to_id);
     }
+
+    #[test]
+    fn test_custom_layout() {
+        let layout = PageLayout::new(1_000, 100).unwrap();
+        let src = QueueWithIntervals::from_single_interval(998, 2_001);
+
+        let result: Vec<SplittedByPageId> =
+            SplittedByPageIdIterator::new_with_layout(&src, layout).collect();
+
+        assert_eq!(3, result.len());
+
+        assert_eq!(0, result[0].page_id.get_value());
+        assert_eq!(998, result[0].ids.intervals[0].from_id);
+        assert_eq!(999, result[0].ids.intervals[0].to_id);
+
+        assert_eq!(1, result[1].page_id.get_value());
+        assert_eq!(1_000, result[1].ids.intervals[0].from_id);
+        assert_eq!(1_999, result[1].ids.intervals[0].to_id);
+
+        assert_eq!(2, result[2].page_id.get_value());
+        assert_eq!(2_000, result[2].ids.intervals[0].from_id);
+        assert_eq!(2_001, result[2].ids.intervals[0].to_id);
+    }
 }
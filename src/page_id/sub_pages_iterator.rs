@@ -1,26 +1,45 @@
+use std::ops::RangeInclusive;
+
 use crate::sub_page::SubPageId;
 
+// Last id is kept inclusive, so the last sub page of a layout may be i64::MAX
 pub struct SubPagesIterator {
-    pub now_id: i64,
-    pub to_id: i64,
+    ids: RangeInclusive<i64>,
+}
+
+impl SubPagesIterator {
+    pub(crate) fn new(ids: RangeInclusive<i64>) -> Self {
+        Self { ids }
+    }
+
+    pub(crate) fn empty() -> Self {
+        // Exhausted range yields nothing from both ends
+        let mut ids = 0..=0;
+        ids.next();
+        Self { ids }
+    }
 }
 
 impl Iterator for SubPagesIterator {
     type Item = SubPageId;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.now_id >= self.to_id {
-            return None;
-        }
-
-        let result = Self::Item::new(self.now_id);
+        self.ids.next().map(Self::Item::new)
+    }
 
-        self.now_id += 1;
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
 
-        Some(result)
+impl DoubleEndedIterator for SubPagesIterator {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.ids.next_back().map(Self::Item::new)
     }
 }
 
+impl ExactSizeIterator for SubPagesIterator {}
+
 #[cfg(test)]
 mod tests {
     use crate::{page_id::PageId, sub_page::SUB_PAGES_PER_PAGE};
@@ -78,4 +97,39 @@ mod tests {
 
         assert_eq!(amount, SUB_PAGES_PER_PAGE);
     }
+
+    #[test]
+    fn test_reverse_and_exact_size() {
+        let page_id = PageId::new(1);
+
+        let mut iterator = page_id.iterate_sub_page_ids();
+        assert_eq!(SUB_PAGES_PER_PAGE as usize, iterator.len());
+
+        assert_eq!(199, iterator.next_back().unwrap().get_value());
+        assert_eq!(100, iterator.next().unwrap().get_value());
+        assert_eq!(SUB_PAGES_PER_PAGE as usize - 2, iterator.len());
+
+        let result: Vec<i64> = page_id
+            .iterate_sub_page_ids()
+            .rev()
+            .take(2)
+            .map(|itm| itm.get_value())
+            .collect();
+
+        assert_eq!(vec![199, 198], result);
+    }
+
+    #[test]
+    fn test_iterate_sub_page_ids_in_range() {
+        let page_id = PageId::new(1);
+
+        let result: Vec<i64> = page_id
+            .iterate_sub_page_ids_in(195..)
+            .map(|itm| itm.get_value())
+            .collect();
+
+        assert_eq!(vec![195, 196, 197, 198, 199], result);
+
+        assert_eq!(0, page_id.iterate_sub_page_ids_in(..100).len());
+    }
 }
@@ -0,0 +1,26 @@
+use prost::DecodeError;
+
+use crate::page_id::InvalidPageId;
+
+#[derive(Debug)]
+pub enum InvalidPagePresenceBitmap {
+    DecodeError(DecodeError),
+    InvalidPageId(InvalidPageId),
+    WrongMessagesInPage { expected: i64, actual: i64 },
+    WrongBitsLength { expected: usize, actual: usize },
+    PaddingBitsAreSet,
+    WrongRunsLength { expected: usize, actual: usize },
+    BothBitsAndRunsAreSet,
+}
+
+impl From<DecodeError> for InvalidPagePresenceBitmap {
+    fn from(src: DecodeError) -> Self {
+        Self::DecodeError(src)
+    }
+}
+
+impl From<InvalidPageId> for InvalidPagePresenceBitmap {
+    fn from(src: InvalidPageId) -> Self {
+        Self::InvalidPageId(src)
+    }
+}
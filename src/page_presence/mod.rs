@@ -0,0 +1,6 @@
+mod error;
+mod page_presence_bitmap;
+mod page_presence_bitmap_model;
+pub use error::*;
+pub use page_presence_bitmap::*;
+pub use page_presence_bitmap_model::*;
@@ -0,0 +1,407 @@
+use my_service_bus_abstractions::{
+    queue_with_intervals::{QueueIndexRange, QueueWithIntervals},
+    MessageId,
+};
+
+use crate::page_id::{InvalidPageId, PageId, PageLayout};
+
+// One bit per message slot of the page. Bit is set if message is present
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PagePresenceBitmap {
+    page_id: PageId,
+    first_message_id: i64,
+    messages_in_page: usize,
+    words: Vec<u64>,
+}
+
+impl PagePresenceBitmap {
+    pub fn new(page_id: PageId) -> Self {
+        Self::new_with_layout(page_id, PageLayout::DEFAULT)
+    }
+
+    // Panics if page id is negative or above the max page id of the layout
+    pub fn new_with_layout(page_id: PageId, layout: PageLayout) -> Self {
+        Self::create(page_id, layout).expect("Page id is out of range of the layout")
+    }
+
+    // Page id is checked the same way as the one read from the wire
+    pub(crate) fn create(page_id: PageId, layout: PageLayout) -> Result<Self, InvalidPageId> {
+        let page_id = PageId::try_new_with_layout(page_id.get_value(), layout)?;
+        let messages_in_page = layout.get_messages_in_page();
+
+        let first_message_id = page_id.get_value() * messages_in_page;
+        let messages_in_page = messages_in_page as usize;
+
+        Ok(Self {
+            page_id,
+            first_message_id,
+            messages_in_page,
+            words: vec![0; messages_in_page.div_ceil(64)],
+        })
+    }
+
+    pub fn from_queue_with_intervals(page_id: PageId, src: &QueueWithIntervals) -> Self {
+        Self::from_queue_with_intervals_with_layout(page_id, src, PageLayout::DEFAULT)
+    }
+
+    // Ids which do not belong to the page are skipped
+    pub fn from_queue_with_intervals_with_layout(
+        page_id: PageId,
+        src: &QueueWithIntervals,
+        layout: PageLayout,
+    ) -> Self {
+        let mut result = Self::new_with_layout(page_id, layout);
+        let last_message_id = result.get_last_message_id();
+
+        for interval in &src.intervals {
+            let from_id = interval.from_id.max(result.first_message_id);
+            let to_id = interval.to_id.min(last_message_id);
+
+            for message_id in from_id..=to_id {
+                result.set_bit(result.get_index(message_id), true);
+            }
+        }
+
+        result
+    }
+
+    pub fn get_page_id(&self) -> PageId {
+        self.page_id
+    }
+
+    pub fn get_messages_in_page(&self) -> usize {
+        self.messages_in_page
+    }
+
+    pub fn is_my_message_id(&self, message_id: MessageId) -> bool {
+        let message_id = message_id.get_value();
+        message_id >= self.first_message_id && message_id <= self.get_last_message_id()
+    }
+
+    // Message ids of other pages are ignored and false is returned
+    pub fn insert(&mut self, message_id: MessageId) -> bool {
+        if !self.is_my_message_id(message_id) {
+            return false;
+        }
+
+        let index = self.get_index(message_id.get_value());
+
+        if self.get_bit(index) {
+            return false;
+        }
+
+        self.set_bit(index, true);
+        true
+    }
+
+    pub fn remove(&mut self, message_id: MessageId) -> bool {
+        if !self.is_my_message_id(message_id) {
+            return false;
+        }
+
+        let index = self.get_index(message_id.get_value());
+
+        if !self.get_bit(index) {
+            return false;
+        }
+
+        self.set_bit(index, false);
+        true
+    }
+
+    pub fn contains(&self, message_id: MessageId) -> bool {
+        if !self.is_my_message_id(message_id) {
+            return false;
+        }
+
+        self.get_bit(self.get_index(message_id.get_value()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.words.iter().map(|itm| itm.count_ones() as usize).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|itm| *itm == 0)
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len() == self.messages_in_page
+    }
+
+    pub fn iter(&self) -> PagePresenceBitmapIterator<'_> {
+        PagePresenceBitmapIterator::new(self, true)
+    }
+
+    pub fn iter_missing(&self) -> PagePresenceBitmapIterator<'_> {
+        PagePresenceBitmapIterator::new(self, false)
+    }
+
+    pub fn to_queue_with_intervals(&self) -> QueueWithIntervals {
+        let mut result = QueueWithIntervals::new();
+
+        let mut from_index = 0;
+
+        for (len, is_present) in self.get_runs() {
+            if is_present {
+                result.intervals.push(QueueIndexRange {
+                    from_id: self.first_message_id + from_index as i64,
+                    to_id: self.first_message_id + (from_index + len) as i64 - 1,
+                });
+            }
+
+            from_index += len;
+        }
+
+        result
+    }
+
+    // Lengths of the runs of the same bit. The first run is always the run of missing messages
+    // and can have zero length
+    pub(crate) fn get_runs(&self) -> Vec<(usize, bool)> {
+        let mut result = Vec::new();
+
+        let mut current = false;
+        let mut len = 0;
+
+        for index in 0..self.messages_in_page {
+            let bit = self.get_bit(index);
+
+            if bit != current {
+                result.push((len, current));
+                current = bit;
+                len = 0;
+            }
+
+            len += 1;
+        }
+
+        result.push((len, current));
+        result
+    }
+
+    pub(crate) fn get_words(&self) -> &[u64] {
+        &self.words
+    }
+
+    // Bits of the last word past messages_in_page have to stay unset, otherwise len and
+    // iterators would count them
+    pub(crate) fn set_words(&mut self, words: Vec<u64>) -> bool {
+        let padding_bits = self.messages_in_page % 64;
+
+        if padding_bits > 0 {
+            if let Some(last) = words.last() {
+                if last & (u64::MAX << padding_bits) != 0 {
+                    return false;
+                }
+            }
+        }
+
+        self.words = words;
+        true
+    }
+
+    pub(crate) fn set_range(&mut self, from_index: usize, len: usize) {
+        for index in from_index..from_index + len {
+            self.set_bit(index, true);
+        }
+    }
+
+    fn get_last_message_id(&self) -> i64 {
+        self.first_message_id + self.messages_in_page as i64 - 1
+    }
+
+    fn get_index(&self, message_id: i64) -> usize {
+        (message_id - self.first_message_id) as usize
+    }
+
+    fn get_bit(&self, index: usize) -> bool {
+        self.words[index / 64] & (1 << (index % 64)) != 0
+    }
+
+    fn set_bit(&mut self, index: usize, value: bool) {
+        if value {
+            self.words[index / 64] |= 1 << (index % 64);
+        } else {
+            self.words[index / 64] &= !(1 << (index % 64));
+        }
+    }
+}
+
+pub struct PagePresenceBitmapIterator<'s> {
+    bitmap: &'s PagePresenceBitmap,
+    present: bool,
+    index: usize,
+}
+
+impl<'s> PagePresenceBitmapIterator<'s> {
+    fn new(bitmap: &'s PagePresenceBitmap, present: bool) -> Self {
+        Self {
+            bitmap,
+            present,
+            index: 0,
+        }
+    }
+}
+
+impl<'s> Iterator for PagePresenceBitmapIterator<'s> {
+    type Item = MessageId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.index < self.bitmap.messages_in_page {
+            // Whole word without interesting bits is skipped at once
+            if self.index.is_multiple_of(64) {
+                let word = self.bitmap.words[self.index / 64];
+                let skip = if self.present {
+                    word == 0
+                } else {
+                    word == u64::MAX
+                };
+
+                if skip {
+                    self.index += 64;
+                    continue;
+                }
+            }
+
+            let index = self.index;
+            self.index += 1;
+
+            if self.bitmap.get_bit(index) == self.present {
+                return Some((self.bitmap.first_message_id + index as i64).into());
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_vec(src: impl Iterator<Item = MessageId>) -> Vec<i64> {
+        src.map(|itm| itm.get_value()).collect()
+    }
+
+    fn to_intervals(src: &QueueWithIntervals) -> Vec<(i64, i64)> {
+        src.intervals
+            .iter()
+            .map(|itm| (itm.from_id, itm.to_id))
+            .collect()
+    }
+
+    #[test]
+    fn test_insert_remove_contains() {
+        let mut bitmap = PagePresenceBitmap::new(PageId::new(1));
+
+        assert!(bitmap.is_empty());
+        assert!(bitmap.insert(100_000.into()));
+        assert!(!bitmap.insert(100_000.into()));
+        assert!(bitmap.insert(199_999.into()));
+        assert!(!bitmap.insert(200_000.into()));
+        assert!(!bitmap.insert(5.into()));
+
+        assert_eq!(2, bitmap.len());
+        assert!(bitmap.contains(100_000.into()));
+        assert!(bitmap.contains(199_999.into()));
+        assert!(!bitmap.contains(100_001.into()));
+        assert!(!bitmap.contains(200_000.into()));
+
+        assert!(bitmap.remove(100_000.into()));
+        assert!(!bitmap.remove(100_000.into()));
+        assert_eq!(1, bitmap.len());
+    }
+
+    #[test]
+    fn test_iterators() {
+        let layout = PageLayout::new(200, 100).unwrap();
+        let mut bitmap = PagePresenceBitmap::new_with_layout(PageId::new(1), layout);
+
+        bitmap.insert(201.into());
+        bitmap.insert(263.into());
+        bitmap.insert(264.into());
+        bitmap.insert(399.into());
+
+        assert_eq!(vec![201, 263, 264, 399], to_vec(bitmap.iter()));
+
+        for message_id in 200..400 {
+            bitmap.insert(message_id.into());
+        }
+
+        assert!(bitmap.is_full());
+        bitmap.remove(250.into());
+        bitmap.remove(330.into());
+
+        assert_eq!(vec![250, 330], to_vec(bitmap.iter_missing()));
+    }
+
+    #[test]
+    fn test_queue_with_intervals_conversion() {
+        let mut src = QueueWithIntervals::from_single_interval(99_990, 100_005);
+        src.intervals.push(QueueIndexRange {
+            from_id: 100_100,
+            to_id: 100_100,
+        });
+        src.intervals.push(QueueIndexRange {
+            from_id: 199_998,
+            to_id: 200_010,
+        });
+
+        let bitmap = PagePresenceBitmap::from_queue_with_intervals(PageId::new(1), &src);
+
+        assert_eq!(6 + 1 + 2, bitmap.len());
+
+        assert_eq!(
+            vec![(100_000, 100_005), (100_100, 100_100), (199_998, 199_999)],
+            to_intervals(&bitmap.to_queue_with_intervals())
+        );
+    }
+
+    #[test]
+    fn test_page_id_out_of_layout() {
+        let max_page_id = PageLayout::DEFAULT.get_max_page_id();
+
+        assert!(PagePresenceBitmap::create(PageId::new(max_page_id), PageLayout::DEFAULT).is_ok());
+
+        for page_id in [max_page_id + 1, i64::MAX, -1, i64::MIN] {
+            assert!(PagePresenceBitmap::create(PageId::new(page_id), PageLayout::DEFAULT).is_err());
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_negative_page_id_panics() {
+        PagePresenceBitmap::new(PageId::new(-1));
+    }
+
+    #[test]
+    fn test_padding_bits() {
+        let layout = PageLayout::new(10, 5).unwrap();
+        let mut bitmap = PagePresenceBitmap::new_with_layout(PageId::new(0), layout);
+
+        assert!(bitmap.set_words(vec![0b11_1111_1111]));
+        assert!(bitmap.is_full());
+
+        assert!(!bitmap.set_words(vec![0b111_1111_1111]));
+        assert!(bitmap.is_full());
+        assert_eq!(10, bitmap.iter().count());
+    }
+
+    #[test]
+    fn test_runs() {
+        let layout = PageLayout::new(10, 5).unwrap();
+        let mut bitmap = PagePresenceBitmap::new_with_layout(PageId::new(0), layout);
+
+        assert_eq!(vec![(10, false)], bitmap.get_runs());
+
+        bitmap.insert(0.into());
+        bitmap.insert(1.into());
+        bitmap.insert(5.into());
+
+        assert_eq!(
+            vec![(0, false), (2, true), (3, false), (1, true), (4, false)],
+            bitmap.get_runs()
+        );
+    }
+}
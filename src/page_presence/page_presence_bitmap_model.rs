@@ -0,0 +1,282 @@
+use prost::EncodeError;
+
+use crate::page_id::{PageId, PageLayout};
+
+use super::{InvalidPagePresenceBitmap, PagePresenceBitmap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PagePresenceBitmapEncoding {
+    // Raw bits, little endian 64 bit words
+    Raw,
+    // Lengths of alternating runs of missing and present messages starting with missing ones.
+    // Good for pages which are mostly full or mostly empty.
+    RunLength,
+}
+
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PagePresenceBitmapProtobufModel {
+    #[prost(int64, tag = "1")]
+    pub page_id: i64,
+    #[prost(int64, tag = "2")]
+    pub messages_in_page: i64,
+    #[prost(bytes = "vec", tag = "3")]
+    pub bits: Vec<u8>,
+    #[prost(uint32, repeated, tag = "4")]
+    pub runs: Vec<u32>,
+}
+
+impl PagePresenceBitmap {
+    pub fn to_protobuf_model(
+        &self,
+        encoding: PagePresenceBitmapEncoding,
+    ) -> PagePresenceBitmapProtobufModel {
+        let mut result = PagePresenceBitmapProtobufModel {
+            page_id: self.get_page_id().get_value(),
+            messages_in_page: self.get_messages_in_page() as i64,
+            bits: Vec::new(),
+            runs: Vec::new(),
+        };
+
+        match encoding {
+            PagePresenceBitmapEncoding::Raw => {
+                for word in self.get_words() {
+                    result.bits.extend_from_slice(&word.to_le_bytes());
+                }
+            }
+            PagePresenceBitmapEncoding::RunLength => {
+                result.runs = self
+                    .get_runs()
+                    .into_iter()
+                    .map(|(len, _)| len as u32)
+                    .collect();
+            }
+        }
+
+        result
+    }
+
+    pub fn from_protobuf_model(
+        src: PagePresenceBitmapProtobufModel,
+    ) -> Result<Self, InvalidPagePresenceBitmap> {
+        Self::from_protobuf_model_with_layout(src, PageLayout::DEFAULT)
+    }
+
+    // Bitmap has to be of the same layout, so the size of the page is never taken from the wire
+    pub fn from_protobuf_model_with_layout(
+        src: PagePresenceBitmapProtobufModel,
+        layout: PageLayout,
+    ) -> Result<Self, InvalidPagePresenceBitmap> {
+        if src.messages_in_page != layout.get_messages_in_page() {
+            return Err(InvalidPagePresenceBitmap::WrongMessagesInPage {
+                expected: layout.get_messages_in_page(),
+                actual: src.messages_in_page,
+            });
+        }
+
+        if !src.bits.is_empty() && !src.runs.is_empty() {
+            return Err(InvalidPagePresenceBitmap::BothBitsAndRunsAreSet);
+        }
+
+        let mut result = Self::create(PageId::new(src.page_id), layout)?;
+
+        if !src.bits.is_empty() {
+            let expected = result.get_words().len() * 8;
+
+            if src.bits.len() != expected {
+                return Err(InvalidPagePresenceBitmap::WrongBitsLength {
+                    expected,
+                    actual: src.bits.len(),
+                });
+            }
+
+            let words = src
+                .bits
+                .chunks(8)
+                .map(|itm| u64::from_le_bytes(itm.try_into().unwrap()))
+                .collect();
+
+            if !result.set_words(words) {
+                return Err(InvalidPagePresenceBitmap::PaddingBitsAreSet);
+            }
+
+            return Ok(result);
+        }
+
+        if src.runs.is_empty() {
+            return Ok(result);
+        }
+
+        let actual: usize = src.runs.iter().map(|itm| *itm as usize).sum();
+
+        if actual != result.get_messages_in_page() {
+            return Err(InvalidPagePresenceBitmap::WrongRunsLength {
+                expected: result.get_messages_in_page(),
+                actual,
+            });
+        }
+
+        let mut from_index = 0;
+
+        for (index, len) in src.runs.iter().enumerate() {
+            let len = *len as usize;
+
+            if index % 2 == 1 {
+                result.set_range(from_index, len);
+            }
+
+            from_index += len;
+        }
+
+        Ok(result)
+    }
+
+    pub fn serialize(
+        &self,
+        encoding: PagePresenceBitmapEncoding,
+        dest: &mut Vec<u8>,
+    ) -> Result<(), EncodeError> {
+        prost::Message::encode(&self.to_protobuf_model(encoding), dest)
+    }
+
+    pub fn parse(payload: &[u8]) -> Result<Self, InvalidPagePresenceBitmap> {
+        Self::parse_with_layout(payload, PageLayout::DEFAULT)
+    }
+
+    pub fn parse_with_layout(
+        payload: &[u8],
+        layout: PageLayout,
+    ) -> Result<Self, InvalidPagePresenceBitmap> {
+        let model: PagePresenceBitmapProtobufModel = prost::Message::decode(payload)?;
+        Self::from_protobuf_model_with_layout(model, layout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_bitmap() -> PagePresenceBitmap {
+        let mut result = PagePresenceBitmap::new(PageId::new(3));
+
+        for message_id in 300_000..350_000 {
+            result.insert(message_id.into());
+        }
+
+        result.remove(300_010.into());
+        result.insert(399_999.into());
+        result
+    }
+
+    #[test]
+    fn test_serialize_and_parse() {
+        let bitmap = create_bitmap();
+
+        for encoding in [
+            PagePresenceBitmapEncoding::Raw,
+            PagePresenceBitmapEncoding::RunLength,
+        ] {
+            let mut payload = Vec::new();
+            bitmap.serialize(encoding, &mut payload).unwrap();
+
+            assert_eq!(bitmap, PagePresenceBitmap::parse(&payload).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_run_length_is_compact_for_continuous_pages() {
+        let bitmap = create_bitmap();
+
+        let mut raw = Vec::new();
+        bitmap
+            .serialize(PagePresenceBitmapEncoding::Raw, &mut raw)
+            .unwrap();
+
+        let mut run_length = Vec::new();
+        bitmap
+            .serialize(PagePresenceBitmapEncoding::RunLength, &mut run_length)
+            .unwrap();
+
+        assert!(raw.len() > 12_500);
+        assert!(run_length.len() < 32);
+    }
+
+    #[test]
+    fn test_empty_bitmap() {
+        let layout = PageLayout::new(100, 10).unwrap();
+        let bitmap = PagePresenceBitmap::new_with_layout(PageId::new(0), layout);
+
+        let mut payload = Vec::new();
+        bitmap
+            .serialize(PagePresenceBitmapEncoding::RunLength, &mut payload)
+            .unwrap();
+
+        assert_eq!(
+            bitmap,
+            PagePresenceBitmap::parse_with_layout(&payload, layout).unwrap()
+        );
+
+        assert!(matches!(
+            PagePresenceBitmap::parse(&payload),
+            Err(InvalidPagePresenceBitmap::WrongMessagesInPage {
+                expected: 100_000,
+                actual: 100
+            })
+        ));
+    }
+
+    #[test]
+    fn test_invalid_models() {
+        let mut model = create_bitmap().to_protobuf_model(PagePresenceBitmapEncoding::RunLength);
+        model.runs.push(1);
+
+        assert!(matches!(
+            PagePresenceBitmap::from_protobuf_model(model),
+            Err(InvalidPagePresenceBitmap::WrongRunsLength { .. })
+        ));
+
+        let mut model = create_bitmap().to_protobuf_model(PagePresenceBitmapEncoding::Raw);
+        model.bits.pop();
+
+        assert!(matches!(
+            PagePresenceBitmap::from_protobuf_model(model),
+            Err(InvalidPagePresenceBitmap::WrongBitsLength { .. })
+        ));
+
+        let mut model = create_bitmap().to_protobuf_model(PagePresenceBitmapEncoding::Raw);
+        model.messages_in_page = i64::MAX;
+
+        assert!(matches!(
+            PagePresenceBitmap::from_protobuf_model(model),
+            Err(InvalidPagePresenceBitmap::WrongMessagesInPage { .. })
+        ));
+
+        for page_id in [-1, i64::MAX] {
+            let mut model = create_bitmap().to_protobuf_model(PagePresenceBitmapEncoding::Raw);
+            model.page_id = page_id;
+
+            assert!(matches!(
+                PagePresenceBitmap::from_protobuf_model(model),
+                Err(InvalidPagePresenceBitmap::InvalidPageId(_))
+            ));
+        }
+
+        // 100_000 is not a multiple of 64, so the last word has padding bits
+        let mut model = create_bitmap().to_protobuf_model(PagePresenceBitmapEncoding::Raw);
+        *model.bits.last_mut().unwrap() = 0x80;
+
+        assert!(matches!(
+            PagePresenceBitmap::from_protobuf_model(model),
+            Err(InvalidPagePresenceBitmap::PaddingBitsAreSet)
+        ));
+
+        let mut model = create_bitmap().to_protobuf_model(PagePresenceBitmapEncoding::Raw);
+        model.runs = create_bitmap()
+            .to_protobuf_model(PagePresenceBitmapEncoding::RunLength)
+            .runs;
+
+        assert!(matches!(
+            PagePresenceBitmap::from_protobuf_model(model),
+            Err(InvalidPagePresenceBitmap::BothBitsAndRunsAreSet)
+        ));
+    }
+}
@@ -0,0 +1,21 @@
+use crate::{page_id::InvalidIdName, validators::InvalidTopicName};
+
+#[derive(Debug, Clone)]
+pub enum InvalidPageStoragePath {
+    WrongSegmentsAmount { expected: usize, actual: usize },
+    InvalidTopicName(InvalidTopicName),
+    InvalidFileName(InvalidIdName),
+    WrongShard { expected: String, actual: String },
+}
+
+impl From<InvalidTopicName> for InvalidPageStoragePath {
+    fn from(src: InvalidTopicName) -> Self {
+        Self::InvalidTopicName(src)
+    }
+}
+
+impl From<InvalidIdName> for InvalidPageStoragePath {
+    fn from(src: InvalidIdName) -> Self {
+        Self::InvalidFileName(src)
+    }
+}
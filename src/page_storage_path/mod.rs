@@ -0,0 +1,6 @@
+mod error;
+mod page_storage_scheme;
+mod storage_path;
+pub use error::*;
+pub use page_storage_scheme::*;
+pub use storage_path::*;
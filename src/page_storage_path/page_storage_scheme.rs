@@ -0,0 +1,142 @@
+use crate::page_id::CANONICAL_ID_WIDTH;
+
+// Ids which share one directory in ByThousands scheme. Has to be a power of 10
+pub const IDS_PER_DIRECTORY: usize = 1000;
+
+// Hash is 16 hex digits, so there are 8 two-digit segments at most
+pub const MAX_HASH_PREFIX_LEVELS: usize = 8;
+
+const THOUSANDS_DIRECTORY_WIDTH: usize = CANONICAL_ID_WIDTH - IDS_PER_DIRECTORY.ilog10() as usize;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidPageStorageScheme {
+    HashPrefixLevelsIsOutOfRange { levels: usize, max_levels: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageStorageScheme {
+    // {topic}/{id}.page
+    Flat,
+    // {topic}/{id / 1000}/{id}.page
+    ByThousands,
+    // {topic}/{ab}/{cd}/{id}.page where abcd are the first hex digits of the file name hash
+    ByHashPrefix { levels: usize },
+}
+
+impl PageStorageScheme {
+    pub fn by_hash_prefix(levels: usize) -> Result<Self, InvalidPageStorageScheme> {
+        let result = PageStorageScheme::ByHashPrefix { levels };
+        result.validate()?;
+        Ok(result)
+    }
+
+    pub fn validate(&self) -> Result<(), InvalidPageStorageScheme> {
+        if let PageStorageScheme::ByHashPrefix { levels } = self {
+            if *levels == 0 || *levels > MAX_HASH_PREFIX_LEVELS {
+                return Err(InvalidPageStorageScheme::HashPrefixLevelsIsOutOfRange {
+                    levels: *levels,
+                    max_levels: MAX_HASH_PREFIX_LEVELS,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn get_shard_segments_amount(&self) -> usize {
+        match self {
+            PageStorageScheme::Flat => 0,
+            PageStorageScheme::ByThousands => 1,
+            PageStorageScheme::ByHashPrefix { levels } => *levels,
+        }
+    }
+
+    pub(crate) fn get_shard_segments(&self, canonical_id: &str) -> Vec<String> {
+        match self {
+            PageStorageScheme::Flat => vec![],
+            PageStorageScheme::ByThousands => {
+                vec![canonical_id[..THOUSANDS_DIRECTORY_WIDTH].to_string()]
+            }
+            PageStorageScheme::ByHashPrefix { levels } => {
+                let hash = format!("{:016x}", fnv1a_hash(canonical_id.as_bytes()));
+
+                hash.as_bytes()
+                    .chunks(2)
+                    .take(*levels)
+                    .map(|itm| String::from_utf8(itm.to_vec()).unwrap())
+                    .collect()
+            }
+        }
+    }
+}
+
+// Hash has to be the same for every service and every build, so std hasher does not fit here
+fn fnv1a_hash(src: &[u8]) -> u64 {
+    let mut result: u64 = 0xcbf29ce484222325;
+
+    for b in src {
+        result ^= *b as u64;
+        result = result.wrapping_mul(0x100000001b3);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fnv1a_hash() {
+        assert_eq!(0xcbf29ce484222325, fnv1a_hash(b""));
+        assert_eq!(0xaf63dc4c8601ec8c, fnv1a_hash(b"a"));
+        assert_eq!(0x85944171f73967e8, fnv1a_hash(b"foobar"));
+    }
+
+    #[test]
+    fn test_hash_prefix_levels() {
+        assert_eq!(
+            Ok(PageStorageScheme::ByHashPrefix { levels: 1 }),
+            PageStorageScheme::by_hash_prefix(1)
+        );
+        assert_eq!(
+            Ok(PageStorageScheme::ByHashPrefix {
+                levels: MAX_HASH_PREFIX_LEVELS
+            }),
+            PageStorageScheme::by_hash_prefix(MAX_HASH_PREFIX_LEVELS)
+        );
+
+        for levels in [0, MAX_HASH_PREFIX_LEVELS + 1] {
+            assert_eq!(
+                Err(InvalidPageStorageScheme::HashPrefixLevelsIsOutOfRange {
+                    levels,
+                    max_levels: MAX_HASH_PREFIX_LEVELS
+                }),
+                PageStorageScheme::by_hash_prefix(levels)
+            );
+        }
+
+        assert!(PageStorageScheme::Flat.validate().is_ok());
+        assert!(PageStorageScheme::ByThousands.validate().is_ok());
+    }
+
+    #[test]
+    fn test_shard_segments() {
+        let canonical_id = "0000000000001234567";
+
+        assert!(PageStorageScheme::Flat
+            .get_shard_segments(canonical_id)
+            .is_empty());
+
+        assert_eq!(
+            vec!["0000000000001234".to_string()],
+            PageStorageScheme::ByThousands.get_shard_segments(canonical_id)
+        );
+
+        let segments =
+            PageStorageScheme::ByHashPrefix { levels: 2 }.get_shard_segments(canonical_id);
+
+        assert_eq!(2, segments.len());
+        assert!(segments.iter().all(|itm| itm.len() == 2));
+    }
+}
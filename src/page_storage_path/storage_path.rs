@@ -0,0 +1,294 @@
+use std::str::FromStr;
+
+use crate::{
+    page_id::{split_file_name, InvalidIdName, PageId, PAGE_FILE_EXTENSION},
+    sub_page::{SubPageId, SUB_PAGE_FILE_EXTENSION},
+    validators::{validate_topic_name, InvalidTopicName},
+};
+
+use super::{InvalidPageStoragePath, InvalidPageStorageScheme, PageStorageScheme};
+
+// Relative path of the page blob. Segments are always separated by '/' so the same path
+// can be used as a blob key and joined to a root directory on any platform.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PageStoragePath {
+    scheme: PageStorageScheme,
+}
+
+impl PageStoragePath {
+    pub fn new(scheme: PageStorageScheme) -> Result<Self, InvalidPageStorageScheme> {
+        scheme.validate()?;
+        Ok(Self { scheme })
+    }
+
+    pub fn get_scheme(&self) -> PageStorageScheme {
+        self.scheme
+    }
+
+    pub fn get_page_path(
+        &self,
+        topic_id: &str,
+        page_id: PageId,
+    ) -> Result<String, InvalidTopicName> {
+        self.build_path(
+            topic_id,
+            &page_id.to_canonical_string(),
+            PAGE_FILE_EXTENSION,
+        )
+    }
+
+    pub fn get_sub_page_path(
+        &self,
+        topic_id: &str,
+        sub_page_id: SubPageId,
+    ) -> Result<String, InvalidTopicName> {
+        self.build_path(
+            topic_id,
+            &sub_page_id.to_canonical_string(),
+            SUB_PAGE_FILE_EXTENSION,
+        )
+    }
+
+    pub fn parse_page_path(&self, path: &str) -> Result<(String, PageId), InvalidPageStoragePath> {
+        self.parse_path(path, PAGE_FILE_EXTENSION)
+    }
+
+    pub fn parse_sub_page_path(
+        &self,
+        path: &str,
+    ) -> Result<(String, SubPageId), InvalidPageStoragePath> {
+        self.parse_path(path, SUB_PAGE_FILE_EXTENSION)
+    }
+
+    fn build_path(
+        &self,
+        topic_id: &str,
+        canonical_id: &str,
+        extension: &str,
+    ) -> Result<String, InvalidTopicName> {
+        validate_topic_name(topic_id)?;
+
+        let mut result = String::new();
+        result.push_str(topic_id);
+
+        for segment in self.scheme.get_shard_segments(canonical_id) {
+            result.push('/');
+            result.push_str(&segment);
+        }
+
+        result.push('/');
+        result.push_str(canonical_id);
+        result.push('.');
+        result.push_str(extension);
+        Ok(result)
+    }
+
+    fn parse_path<TId: FromStr<Err = InvalidIdName>>(
+        &self,
+        path: &str,
+        extension: &'static str,
+    ) -> Result<(String, TId), InvalidPageStoragePath> {
+        let segments: Vec<&str> = path.trim_start_matches('/').split('/').collect();
+
+        let expected = self.scheme.get_shard_segments_amount() + 2;
+
+        if segments.len() != expected {
+            return Err(InvalidPageStoragePath::WrongSegmentsAmount {
+                expected,
+                actual: segments.len(),
+            });
+        }
+
+        let topic_id = segments[0];
+        validate_topic_name(topic_id)?;
+
+        let file_name = segments[segments.len() - 1];
+        let canonical_id = split_file_name(file_name, extension)?;
+        let id = TId::from_str(canonical_id)?;
+
+        let expected_shard = self.scheme.get_shard_segments(canonical_id).join("/");
+        let actual_shard = segments[1..segments.len() - 1].join("/");
+
+        if expected_shard != actual_shard {
+            return Err(InvalidPageStoragePath::WrongShard {
+                expected: expected_shard,
+                actual: actual_shard,
+            });
+        }
+
+        Ok((topic_id.to_string(), id))
+    }
+}
+
+impl Default for PageStoragePath {
+    fn default() -> Self {
+        Self {
+            scheme: PageStorageScheme::ByThousands,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCHEMES: [PageStorageScheme; 4] = [
+        PageStorageScheme::Flat,
+        PageStorageScheme::ByThousands,
+        PageStorageScheme::ByHashPrefix { levels: 1 },
+        PageStorageScheme::ByHashPrefix { levels: 3 },
+    ];
+
+    #[test]
+    fn test_flat_path() {
+        let path = PageStoragePath::new(PageStorageScheme::Flat).unwrap();
+
+        assert_eq!(
+            "my-topic/0000000000000000015.page",
+            path.get_page_path("my-topic", PageId::new(15)).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_by_thousands_path() {
+        let path = PageStoragePath::new(PageStorageScheme::ByThousands).unwrap();
+
+        assert_eq!(
+            "my-topic/0000000000000001/0000000000000001234.page",
+            path.get_page_path("my-topic", PageId::new(1234)).unwrap()
+        );
+
+        assert_eq!(
+            "my-topic/0000000000000012/0000000000000012345.subpage",
+            path.get_sub_page_path("my-topic", SubPageId::new(12_345))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_by_hash_prefix_path() {
+        // Layout must be stable between services and releases
+        let one_level =
+            PageStoragePath::new(PageStorageScheme::ByHashPrefix { levels: 1 }).unwrap();
+        let three_levels =
+            PageStoragePath::new(PageStorageScheme::ByHashPrefix { levels: 3 }).unwrap();
+
+        for (id, expected_one_level, expected_three_levels) in [
+            (
+                0,
+                "my-topic/b9/0000000000000000000.page",
+                "my-topic/b9/94/8e/0000000000000000000.page",
+            ),
+            (
+                1234,
+                "my-topic/f3/0000000000000001234.page",
+                "my-topic/f3/fb/42/0000000000000001234.page",
+            ),
+            (
+                123_456_789,
+                "my-topic/a6/0000000000123456789.page",
+                "my-topic/a6/74/67/0000000000123456789.page",
+            ),
+        ] {
+            assert_eq!(
+                expected_one_level,
+                one_level
+                    .get_page_path("my-topic", PageId::new(id))
+                    .unwrap()
+            );
+            assert_eq!(
+                expected_three_levels,
+                three_levels
+                    .get_page_path("my-topic", PageId::new(id))
+                    .unwrap()
+            );
+        }
+
+        assert_eq!(
+            "my-topic/0d/0000000000000012345.subpage",
+            one_level
+                .get_sub_page_path("my-topic", SubPageId::new(12_345))
+                .unwrap()
+        );
+        assert_eq!(
+            "my-topic/0d/77/be/0000000000000012345.subpage",
+            three_levels
+                .get_sub_page_path("my-topic", SubPageId::new(12_345))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    fn test_inverse_mapping() {
+        for scheme in SCHEMES {
+            let path = PageStoragePath::new(scheme).unwrap();
+
+            for id in [0, 1, 999, 1_000, 123_456_789] {
+                let page_path = path.get_page_path("my-topic", PageId::new(id)).unwrap();
+                let (topic_id, page_id) = path.parse_page_path(&page_path).unwrap();
+
+                assert_eq!("my-topic", topic_id);
+                assert_eq!(id, page_id.get_value());
+
+                let sub_page_path = path
+                    .get_sub_page_path("my-topic", SubPageId::new(id))
+                    .unwrap();
+                let (topic_id, sub_page_id) = path.parse_sub_page_path(&sub_page_path).unwrap();
+
+                assert_eq!("my-topic", topic_id);
+                assert_eq!(id, sub_page_id.get_value());
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_topic_or_scheme() {
+        let path = PageStoragePath::default();
+
+        assert!(matches!(
+            path.get_page_path("../other-topic", PageId::new(1)),
+            Err(InvalidTopicName::InvalidNameFormat(_))
+        ));
+
+        assert!(matches!(
+            path.get_sub_page_path("topics", SubPageId::new(1)),
+            Err(InvalidTopicName::NameIsReserved)
+        ));
+
+        assert_eq!(
+            Err(InvalidPageStorageScheme::HashPrefixLevelsIsOutOfRange {
+                levels: 9,
+                max_levels: 8
+            }),
+            PageStoragePath::new(PageStorageScheme::ByHashPrefix { levels: 9 })
+        );
+    }
+
+    #[test]
+    fn test_invalid_paths() {
+        let path = PageStoragePath::new(PageStorageScheme::ByThousands).unwrap();
+
+        assert!(matches!(
+            path.parse_page_path("my-topic/0000000000000001234.page"),
+            Err(InvalidPageStoragePath::WrongSegmentsAmount {
+                expected: 3,
+                actual: 2
+            })
+        ));
+
+        assert!(matches!(
+            path.parse_page_path("my-topic/0000000000000002/0000000000000001234.page"),
+            Err(InvalidPageStoragePath::WrongShard { .. })
+        ));
+
+        assert!(matches!(
+            path.parse_page_path("My_Topic/0000000000000001/0000000000000001234.page"),
+            Err(InvalidPageStoragePath::InvalidTopicName(_))
+        ));
+
+        assert!(matches!(
+            path.parse_page_path("my-topic/0000000000000001/0000000000000001234.subpage"),
+            Err(InvalidPageStoragePath::InvalidFileName(_))
+        ));
+    }
+}
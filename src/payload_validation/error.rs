@@ -0,0 +1,5 @@
+#[derive(Debug, Clone)]
+pub enum PayloadValidationError {
+    UnknownSchema(String),
+    InvalidPayload { schema_id: String, reason: String },
+}
@@ -0,0 +1,8 @@
+mod error;
+mod payload_schema_registry;
+mod payload_validator;
+mod protobuf_descriptor_validator;
+pub use error::*;
+pub use payload_schema_registry::*;
+pub use payload_validator::*;
+pub use protobuf_descriptor_validator::*;
@@ -0,0 +1,186 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::protobuf_models::MessageProtobufModel;
+
+use super::{PayloadValidationError, PayloadValidator};
+
+pub const SCHEMA_ID_HEADER: &str = "schema-id";
+
+pub trait PayloadSchemaRegistry {
+    fn get_validator(&self, schema_id: &str) -> Option<Arc<PayloadValidator>>;
+}
+
+pub struct InMemoryPayloadSchemaRegistry {
+    validators: HashMap<String, Arc<PayloadValidator>>,
+}
+
+impl InMemoryPayloadSchemaRegistry {
+    pub fn new() -> Self {
+        Self {
+            validators: HashMap::new(),
+        }
+    }
+
+    pub fn register(&mut self, schema_id: impl Into<String>, validator: PayloadValidator) {
+        self.validators
+            .insert(schema_id.into(), Arc::new(validator));
+    }
+
+    pub fn unregister(&mut self, schema_id: &str) -> bool {
+        self.validators.remove(schema_id).is_some()
+    }
+}
+
+impl Default for InMemoryPayloadSchemaRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PayloadSchemaRegistry for InMemoryPayloadSchemaRegistry {
+    fn get_validator(&self, schema_id: &str) -> Option<Arc<PayloadValidator>> {
+        self.validators.get(schema_id).cloned()
+    }
+}
+
+// Schema is taken from the schema-id header, or from the content type if there is no header.
+// Content type is looked up by its media type only - parameters like "; charset=utf-8" are ignored.
+// Unknown schema id is an error, while content types without registered schema are accepted as is.
+pub fn validate_message(
+    registry: &impl PayloadSchemaRegistry,
+    message: &MessageProtobufModel,
+) -> Result<(), PayloadValidationError> {
+    let (schema_id, validator) = match message.get_header(SCHEMA_ID_HEADER) {
+        Some(schema_id) => {
+            let validator = registry
+                .get_validator(schema_id)
+                .ok_or_else(|| PayloadValidationError::UnknownSchema(schema_id.to_string()))?;
+
+            (schema_id, validator)
+        }
+        None => {
+            let validator = message.get_content_type().and_then(|content_type| {
+                let media_type = get_media_type(content_type);
+                Some((media_type, registry.get_validator(media_type)?))
+            });
+
+            match validator {
+                Some(validator) => validator,
+                None => return Ok(()),
+            }
+        }
+    };
+
+    validator
+        .validate(message.data.as_slice())
+        .map_err(|reason| PayloadValidationError::InvalidPayload {
+            schema_id: schema_id.to_string(),
+            reason,
+        })
+}
+
+fn get_media_type(content_type: &str) -> &str {
+    match content_type.split_once(';') {
+        Some((media_type, _)) => media_type.trim(),
+        None => content_type.trim(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::*;
+
+    fn create_message(data: &str) -> MessageProtobufModel {
+        MessageProtobufModel::new(
+            1.into(),
+            DateTimeAsMicroseconds::new(0),
+            data.as_bytes().to_vec(),
+            vec![],
+        )
+    }
+
+    fn create_registry() -> InMemoryPayloadSchemaRegistry {
+        let mut registry = InMemoryPayloadSchemaRegistry::new();
+        registry.register("application/json", PayloadValidator::Json);
+        registry.register(
+            "short-text",
+            PayloadValidator::custom(|payload| {
+                if payload.len() > 5 {
+                    return Err("Too long".to_string());
+                }
+                Ok(())
+            }),
+        );
+        registry
+    }
+
+    #[test]
+    fn test_message_without_schema_is_accepted() {
+        let registry = create_registry();
+        assert!(validate_message(&registry, &create_message("not a json")).is_ok());
+    }
+
+    #[test]
+    fn test_content_type_without_schema_is_accepted() {
+        let registry = create_registry();
+
+        let mut msg = create_message("plain text");
+        msg.set_content_type(Some("text/plain".to_string()));
+
+        assert!(validate_message(&registry, &msg).is_ok());
+    }
+
+    #[test]
+    fn test_validation_by_content_type() {
+        let registry = create_registry();
+
+        let mut msg = create_message("{\"a\":1}");
+        msg.set_content_type(Some("application/json".to_string()));
+        assert!(validate_message(&registry, &msg).is_ok());
+
+        msg.data = "{\"a\":".as_bytes().to_vec();
+        assert!(matches!(
+            validate_message(&registry, &msg),
+            Err(PayloadValidationError::InvalidPayload { .. })
+        ));
+    }
+
+    #[test]
+    fn test_content_type_parameters_are_ignored() {
+        let registry = create_registry();
+
+        let mut msg = create_message("{\"a\":");
+        msg.set_content_type(Some("application/json; charset=utf-8".to_string()));
+
+        assert!(matches!(
+            validate_message(&registry, &msg),
+            Err(PayloadValidationError::InvalidPayload { schema_id, .. }) if schema_id == "application/json"
+        ));
+
+        msg.data = "{\"a\":1}".as_bytes().to_vec();
+        assert!(validate_message(&registry, &msg).is_ok());
+    }
+
+    #[test]
+    fn test_schema_id_header_wins_over_content_type() {
+        let mut registry = create_registry();
+
+        let mut msg = create_message("{\"a\":1}");
+        msg.set_content_type(Some("application/json".to_string()));
+        msg.set_header(SCHEMA_ID_HEADER, "short-text");
+
+        assert!(matches!(
+            validate_message(&registry, &msg),
+            Err(PayloadValidationError::InvalidPayload { schema_id, .. }) if schema_id == "short-text"
+        ));
+
+        assert!(registry.unregister("short-text"));
+
+        assert!(matches!(
+            validate_message(&registry, &msg),
+            Err(PayloadValidationError::UnknownSchema(_))
+        ));
+    }
+}
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use prost_types::FileDescriptorSet;
+
+use super::validate_protobuf_payload;
+
+pub type PayloadValidationCallback = dyn Fn(&[u8]) -> Result<(), String> + Send + Sync;
+
+pub enum PayloadValidator {
+    Json,
+    ProtobufDescriptor {
+        descriptor_set: FileDescriptorSet,
+        message_name: String,
+    },
+    Custom(Arc<PayloadValidationCallback>),
+}
+
+impl PayloadValidator {
+    pub fn custom(callback: impl Fn(&[u8]) -> Result<(), String> + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(callback))
+    }
+
+    // Message name is fully qualified: ".package.Message"
+    pub fn protobuf_descriptor(
+        descriptor_set: FileDescriptorSet,
+        message_name: impl Into<String>,
+    ) -> Self {
+        Self::ProtobufDescriptor {
+            descriptor_set,
+            message_name: message_name.into(),
+        }
+    }
+
+    pub fn validate(&self, payload: &[u8]) -> Result<(), String> {
+        match self {
+            PayloadValidator::Json => match serde_json::from_slice::<serde_json::Value>(payload) {
+                Ok(_) => Ok(()),
+                Err(err) => Err(format!("Invalid json: {}", err)),
+            },
+            PayloadValidator::ProtobufDescriptor {
+                descriptor_set,
+                message_name,
+            } => validate_protobuf_payload(&descriptor_set.file, message_name, payload),
+            PayloadValidator::Custom(callback) => callback(payload),
+        }
+    }
+}
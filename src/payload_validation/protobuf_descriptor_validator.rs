@@ -0,0 +1,507 @@
+use std::collections::HashMap;
+
+use prost_types::{
+    field_descriptor_proto::{Label, Type},
+    DescriptorProto, FieldDescriptorProto, FileDescriptorProto,
+};
+
+const WIRE_TYPE_VARINT: u64 = 0;
+const WIRE_TYPE_FIXED64: u64 = 1;
+const WIRE_TYPE_LEN: u64 = 2;
+const WIRE_TYPE_FIXED32: u64 = 5;
+
+const MAX_DEPTH: usize = 100;
+
+// Checks that payload is a well formed protobuf message which fields match the descriptors.
+// Message is looked up by its fully qualified name (e.g. ".package.Message", leading dot is optional)
+// among all the messages declared by the files. Unknown fields are skipped the same way protobuf
+// decoders do. Message fields are validated recursively.
+pub fn validate_protobuf_payload(
+    files: &[FileDescriptorProto],
+    message_name: &str,
+    payload: &[u8],
+) -> Result<(), String> {
+    let mut types = HashMap::new();
+
+    for file in files {
+        let package = match file.package() {
+            "" => String::new(),
+            package => format!(".{}", package),
+        };
+
+        for descriptor in &file.message_type {
+            collect_types(
+                descriptor,
+                format!("{}.{}", package, descriptor.name()),
+                &mut types,
+            );
+        }
+    }
+
+    let message_name = format!(".{}", message_name.trim_start_matches('.'));
+
+    let (full_name, descriptor) = types
+        .get_key_value(message_name.as_str())
+        .ok_or_else(|| format!("Message type {} is not declared", message_name))?;
+
+    validate_message(full_name, descriptor, &types, payload, 0)
+}
+
+// Types are registered by their fully qualified names with the leading dot
+fn collect_types<'s>(
+    descriptor: &'s DescriptorProto,
+    full_name: String,
+    types: &mut HashMap<String, &'s DescriptorProto>,
+) {
+    for nested in &descriptor.nested_type {
+        collect_types(nested, format!("{}.{}", full_name, nested.name()), types);
+    }
+
+    types.insert(full_name, descriptor);
+}
+
+fn resolve_type<'t, 's>(
+    types: &'t HashMap<String, &'s DescriptorProto>,
+    scope: &str,
+    type_name: &str,
+) -> Option<(&'t str, &'s DescriptorProto)> {
+    if type_name.starts_with('.') {
+        return types
+            .get_key_value(type_name)
+            .map(|(name, descriptor)| (name.as_str(), *descriptor));
+    }
+
+    // Relative names are looked up from the innermost scope outwards
+    let mut scope = Some(scope);
+
+    while let Some(current) = scope {
+        let full_name = format!("{}.{}", current, type_name);
+
+        if let Some((name, descriptor)) = types.get_key_value(full_name.as_str()) {
+            return Some((name.as_str(), *descriptor));
+        }
+
+        scope = current.rsplit_once('.').map(|itm| itm.0);
+    }
+
+    None
+}
+
+fn validate_message(
+    full_name: &str,
+    descriptor: &DescriptorProto,
+    types: &HashMap<String, &DescriptorProto>,
+    mut payload: &[u8],
+    depth: usize,
+) -> Result<(), String> {
+    if depth > MAX_DEPTH {
+        return Err(format!("Message nesting is deeper than {}", MAX_DEPTH));
+    }
+
+    while !payload.is_empty() {
+        let key = decode_varint(&mut payload)?;
+        let tag = key >> 3;
+        let wire_type = key & 0x07;
+
+        let field = match descriptor
+            .field
+            .iter()
+            .find(|itm| itm.number() as u64 == tag)
+        {
+            Some(field) => field,
+            None => {
+                payload = skip_unknown_field(payload, tag, wire_type)?;
+                continue;
+            }
+        };
+
+        let expected = match get_wire_type(field) {
+            Some(expected) => expected,
+            None => return Err(format!("Field {} has unsupported type", field.name())),
+        };
+
+        if expected != wire_type && !is_packed(field, expected, wire_type) {
+            return Err(format!(
+                "Field {} has wire type {} which does not match its declared type",
+                field.name(),
+                wire_type
+            ));
+        }
+
+        match wire_type {
+            WIRE_TYPE_VARINT => {
+                decode_varint(&mut payload)?;
+            }
+            WIRE_TYPE_FIXED64 => {
+                payload = skip(payload, 8, field.name())?;
+            }
+            WIRE_TYPE_FIXED32 => {
+                payload = skip(payload, 4, field.name())?;
+            }
+            _ => {
+                let len = decode_varint(&mut payload)? as usize;
+                let value = payload
+                    .get(..len)
+                    .ok_or_else(|| format!("Field {} is truncated", field.name()))?;
+
+                if expected == WIRE_TYPE_LEN {
+                    validate_len_value(full_name, field, types, value, depth)?;
+                } else {
+                    validate_packed_value(field, expected, value)?;
+                }
+
+                payload = &payload[len..];
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn validate_len_value(
+    scope: &str,
+    field: &FieldDescriptorProto,
+    types: &HashMap<String, &DescriptorProto>,
+    value: &[u8],
+    depth: usize,
+) -> Result<(), String> {
+    match field.r#type() {
+        Type::String if std::str::from_utf8(value).is_err() => {
+            return Err(format!("Field {} is not a valid utf8 string", field.name()));
+        }
+        Type::Message => {
+            let (full_name, nested) =
+                resolve_type(types, scope, field.type_name()).ok_or_else(|| {
+                    format!(
+                        "Type {} of field {} is not declared",
+                        field.type_name(),
+                        field.name()
+                    )
+                })?;
+
+            validate_message(full_name, nested, types, value, depth + 1)?;
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn validate_packed_value(
+    field: &FieldDescriptorProto,
+    expected: u64,
+    mut value: &[u8],
+) -> Result<(), String> {
+    match expected {
+        WIRE_TYPE_VARINT => {
+            while !value.is_empty() {
+                decode_varint(&mut value)?;
+            }
+        }
+        WIRE_TYPE_FIXED64 if !value.len().is_multiple_of(8) => {
+            return Err(format!("Packed field {} is truncated", field.name()));
+        }
+        WIRE_TYPE_FIXED32 if !value.len().is_multiple_of(4) => {
+            return Err(format!("Packed field {} is truncated", field.name()));
+        }
+        _ => {}
+    }
+
+    Ok(())
+}
+
+fn skip_unknown_field(mut payload: &[u8], tag: u64, wire_type: u64) -> Result<&[u8], String> {
+    let name = format!("with tag {}", tag);
+
+    match wire_type {
+        WIRE_TYPE_VARINT => {
+            decode_varint(&mut payload)?;
+            Ok(payload)
+        }
+        WIRE_TYPE_FIXED64 => skip(payload, 8, name.as_str()),
+        WIRE_TYPE_FIXED32 => skip(payload, 4, name.as_str()),
+        WIRE_TYPE_LEN => {
+            let len = decode_varint(&mut payload)? as usize;
+            skip(payload, len, name.as_str())
+        }
+        _ => Err(format!(
+            "Field with tag {} has unsupported wire type {}",
+            tag, wire_type
+        )),
+    }
+}
+
+fn get_wire_type(field: &FieldDescriptorProto) -> Option<u64> {
+    let result = match field.r#type() {
+        Type::Int64
+        | Type::Uint64
+        | Type::Int32
+        | Type::Uint32
+        | Type::Bool
+        | Type::Enum
+        | Type::Sint32
+        | Type::Sint64 => WIRE_TYPE_VARINT,
+        Type::Double | Type::Fixed64 | Type::Sfixed64 => WIRE_TYPE_FIXED64,
+        Type::Float | Type::Fixed32 | Type::Sfixed32 => WIRE_TYPE_FIXED32,
+        Type::String | Type::Bytes | Type::Message => WIRE_TYPE_LEN,
+        Type::Group => return None,
+    };
+
+    Some(result)
+}
+
+// Repeated scalars may come packed
+fn is_packed(field: &FieldDescriptorProto, expected: u64, wire_type: u64) -> bool {
+    wire_type == WIRE_TYPE_LEN && expected != WIRE_TYPE_LEN && field.label() == Label::Repeated
+}
+
+fn skip<'s>(payload: &'s [u8], len: usize, field_name: &str) -> Result<&'s [u8], String> {
+    if payload.len() < len {
+        return Err(format!("Field {} is truncated", field_name));
+    }
+
+    Ok(&payload[len..])
+}
+
+fn decode_varint(payload: &mut &[u8]) -> Result<u64, String> {
+    prost::encoding::decode_varint(payload).map_err(|err| format!("Invalid varint: {}", err))
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::*;
+    use crate::protobuf_models::{MessageMetaDataProtobufModel, MessageProtobufModel};
+
+    fn field(name: &str, number: i32, field_type: Type, label: Label) -> FieldDescriptorProto {
+        FieldDescriptorProto {
+            name: Some(name.to_string()),
+            number: Some(number),
+            r#type: Some(field_type as i32),
+            label: Some(label as i32),
+            ..Default::default()
+        }
+    }
+
+    const MESSAGE: &str = ".my_service_bus_persistence.MessageProtobufModel";
+
+    // Same layout as proto/my_service_bus_persistence.proto: headers type is a top level message
+    fn message_file() -> FileDescriptorProto {
+        let mut headers = field("headers", 4, Type::Message, Label::Repeated);
+        headers.type_name =
+            Some(".my_service_bus_persistence.MessageMetaDataProtobufModel".to_string());
+
+        FileDescriptorProto {
+            name: Some("my_service_bus_persistence.proto".to_string()),
+            package: Some("my_service_bus_persistence".to_string()),
+            message_type: vec![
+                DescriptorProto {
+                    name: Some("MessageMetaDataProtobufModel".to_string()),
+                    field: vec![
+                        field("key", 1, Type::String, Label::Optional),
+                        field("value", 2, Type::String, Label::Optional),
+                    ],
+                    ..Default::default()
+                },
+                DescriptorProto {
+                    name: Some("MessageProtobufModel".to_string()),
+                    field: vec![
+                        field("message_id", 1, Type::Int64, Label::Optional),
+                        field("created", 2, Type::Int64, Label::Optional),
+                        field("data", 3, Type::Bytes, Label::Optional),
+                        headers,
+                    ],
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        }
+    }
+
+    fn message_descriptor(file: &mut FileDescriptorProto) -> &mut DescriptorProto {
+        &mut file.message_type[1]
+    }
+
+    fn file_with_message(name: &str, field: FieldDescriptorProto) -> FileDescriptorProto {
+        FileDescriptorProto {
+            package: Some("test".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some(name.to_string()),
+                field: vec![field],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }
+    }
+
+    fn serialize_message() -> Vec<u8> {
+        let model = MessageProtobufModel::new(
+            1.into(),
+            DateTimeAsMicroseconds::new(1_000),
+            vec![1, 2, 3],
+            vec![MessageMetaDataProtobufModel::new("k", "v")],
+        );
+
+        let mut payload = Vec::new();
+        model.serialize(&mut payload).unwrap();
+        payload
+    }
+
+    #[test]
+    fn test_valid_payload() {
+        let files = [message_file()];
+
+        assert!(validate_protobuf_payload(&files, MESSAGE, &serialize_message()).is_ok());
+        assert!(validate_protobuf_payload(
+            &files,
+            "my_service_bus_persistence.MessageProtobufModel",
+            &serialize_message()
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn test_unknown_message_type() {
+        let files = [message_file()];
+
+        assert!(
+            validate_protobuf_payload(&files, "MessageProtobufModel", &serialize_message())
+                .is_err()
+        );
+        assert!(validate_protobuf_payload(
+            &files,
+            ".other.MessageProtobufModel",
+            &serialize_message()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_truncated_payload() {
+        let payload = serialize_message();
+
+        assert!(validate_protobuf_payload(
+            &[message_file()],
+            MESSAGE,
+            &payload[..payload.len() - 1]
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_unknown_field_is_skipped() {
+        let mut file = message_file();
+        message_descriptor(&mut file)
+            .field
+            .retain(|itm| itm.number() != 3);
+        let files = [file];
+
+        assert!(validate_protobuf_payload(&files, MESSAGE, &serialize_message()).is_ok());
+
+        let payload = serialize_message();
+        // Unknown data field is cut in the middle
+        assert!(
+            validate_protobuf_payload(&files, MESSAGE, &payload[..payload.len() - 10]).is_err()
+        );
+    }
+
+    #[test]
+    fn test_type_resolution() {
+        let mut file = message_file();
+
+        message_descriptor(&mut file).field[3].type_name =
+            Some("MessageMetaDataProtobufModel".to_string());
+        assert!(validate_protobuf_payload(&[file.clone()], MESSAGE, &serialize_message()).is_ok());
+
+        message_descriptor(&mut file).field[3].type_name =
+            Some("my_service_bus_persistence.MessageMetaDataProtobufModel".to_string());
+        assert!(validate_protobuf_payload(&[file.clone()], MESSAGE, &serialize_message()).is_ok());
+
+        message_descriptor(&mut file).field[3].type_name =
+            Some(".MessageMetaDataProtobufModel".to_string());
+        assert!(validate_protobuf_payload(&[file.clone()], MESSAGE, &serialize_message()).is_err());
+
+        message_descriptor(&mut file).field[3].type_name =
+            Some(".my_service_bus_persistence.Unknown".to_string());
+        assert!(validate_protobuf_payload(&[file], MESSAGE, &serialize_message()).is_err());
+    }
+
+    #[test]
+    fn test_types_are_resolved_across_files() {
+        let mut file = message_file();
+        let headers_type = file.message_type.remove(0);
+
+        let headers_file = FileDescriptorProto {
+            name: Some("headers.proto".to_string()),
+            package: Some("my_service_bus_persistence".to_string()),
+            message_type: vec![headers_type],
+            ..Default::default()
+        };
+
+        assert!(validate_protobuf_payload(&[file.clone()], MESSAGE, &serialize_message()).is_err());
+        assert!(
+            validate_protobuf_payload(&[file, headers_file], MESSAGE, &serialize_message()).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_nesting_depth_is_limited() {
+        let mut child = field("child", 1, Type::Message, Label::Optional);
+        child.type_name = Some(".test.Node".to_string());
+
+        let files = [file_with_message("Node", child)];
+
+        let create_payload = |depth: usize| {
+            let mut result = Vec::new();
+
+            for _ in 0..depth {
+                let mut node = vec![0x0a];
+                prost::encoding::encode_varint(result.len() as u64, &mut node);
+                node.extend_from_slice(result.as_slice());
+                result = node;
+            }
+
+            result
+        };
+
+        assert!(validate_protobuf_payload(&files, "test.Node", &create_payload(MAX_DEPTH)).is_ok());
+        assert!(
+            validate_protobuf_payload(&files, "test.Node", &create_payload(MAX_DEPTH + 1)).is_err()
+        );
+    }
+
+    #[test]
+    fn test_nested_message_is_validated() {
+        let mut file = message_file();
+        file.message_type[0].field[1] = field("value", 2, Type::Int64, Label::Optional);
+
+        assert!(validate_protobuf_payload(&[file], MESSAGE, &serialize_message()).is_err());
+    }
+
+    #[test]
+    fn test_packed_repeated_field() {
+        let files = [file_with_message(
+            "Ids",
+            field("ids", 1, Type::Int64, Label::Repeated),
+        )];
+
+        assert!(validate_protobuf_payload(&files, "test.Ids", &[0x0a, 0x02, 0x01, 0x02]).is_ok());
+        assert!(validate_protobuf_payload(&files, "test.Ids", &[0x0a, 0x02, 0x01, 0x82]).is_err());
+
+        let files = [file_with_message(
+            "Ids",
+            field("ids", 1, Type::Fixed32, Label::Repeated),
+        )];
+
+        assert!(validate_protobuf_payload(
+            &files,
+            "test.Ids",
+            &[0x0a, 0x04, 0x01, 0x02, 0x03, 0x04]
+        )
+        .is_ok());
+        assert!(
+            validate_protobuf_payload(&files, "test.Ids", &[0x0a, 0x03, 0x01, 0x02, 0x03]).is_err()
+        );
+    }
+}
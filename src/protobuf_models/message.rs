@@ -1,24 +1,74 @@
+use std::time::Duration;
+
+use bytes::Bytes;
 use my_service_bus_abstractions::MessageId;
 use prost::{DecodeError, EncodeError};
 use rust_extensions::date_time::DateTimeAsMicroseconds;
 
+// Payload of the message. Vec<u8> owns the data, Bytes shares the buffer the message is decoded from
+pub trait MessageData:
+    prost::encoding::BytesAdapter
+    + AsRef<[u8]>
+    + From<Vec<u8>>
+    + Clone
+    + PartialEq
+    + for<'s> PartialEq<&'s [u8]>
+    + std::fmt::Debug
+    + Send
+    + Sync
+{
+    fn clear(&mut self);
+}
+
+impl MessageData for Vec<u8> {
+    fn clear(&mut self) {
+        Vec::clear(self)
+    }
+}
+
+impl MessageData for Bytes {
+    fn clear(&mut self) {
+        Bytes::clear(self)
+    }
+}
+
+pub type MessageProtobufModel = GenericMessageProtobufModel<Vec<u8>>;
+
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct MessageProtobufModel {
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GenericMessageProtobufModel<TData: MessageData> {
     #[prost(int64, tag = "1")]
-    message_id: i64,
+    pub(super) message_id: i64,
     #[prost(int64, tag = "2")]
-    created: i64,
+    pub(super) created: i64,
     #[prost(bytes, tag = "3")]
-    pub data: Vec<u8>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_base64"))]
+    pub data: TData,
     #[prost(message, repeated, tag = "4")]
     pub headers: Vec<MessageMetaDataProtobufModel>,
+    #[prost(int64, optional, tag = "5")]
+    pub(super) expires: Option<i64>,
+    #[prost(int64, optional, tag = "6")]
+    pub(super) ttl: Option<i64>,
+    #[prost(string, optional, tag = "7")]
+    pub(super) partition_key: Option<String>,
+    #[prost(string, optional, tag = "8")]
+    pub(super) correlation_id: Option<String>,
+    #[prost(string, optional, tag = "9")]
+    pub(super) content_type: Option<String>,
 }
 
 impl MessageProtobufModel {
+    pub fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
+        prost::Message::decode(payload)
+    }
+}
+
+impl<TData: MessageData> GenericMessageProtobufModel<TData> {
     pub fn new(
         message_id: MessageId,
         create: DateTimeAsMicroseconds,
-        data: Vec<u8>,
+        data: TData,
         headers: Vec<MessageMetaDataProtobufModel>,
     ) -> Self {
         Self {
@@ -26,13 +76,14 @@ impl MessageProtobufModel {
             created: create.unix_microseconds,
             data,
             headers,
+            expires: None,
+            ttl: None,
+            partition_key: None,
+            correlation_id: None,
+            content_type: None,
         }
     }
 
-    pub fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
-        prost::Message::decode(payload)
-    }
-
     pub fn serialize(&self, dest: &mut Vec<u8>) -> Result<(), EncodeError> {
         prost::Message::encode(self, dest)
     }
@@ -44,28 +95,188 @@ impl MessageProtobufModel {
     pub fn get_created(&self) -> DateTimeAsMicroseconds {
         DateTimeAsMicroseconds::new(self.created)
     }
+
+    pub fn get_expires(&self) -> Option<DateTimeAsMicroseconds> {
+        self.expires.map(DateTimeAsMicroseconds::new)
+    }
+
+    pub fn set_expires(&mut self, expires: Option<DateTimeAsMicroseconds>) {
+        self.expires = expires.map(|itm| itm.unix_microseconds);
+    }
+
+    pub fn get_ttl(&self) -> Option<Duration> {
+        self.ttl.map(micros_to_duration)
+    }
+
+    pub fn set_ttl(&mut self, ttl: Option<Duration>) {
+        self.ttl = ttl.map(duration_to_micros);
+    }
+
+    pub fn expires_at(&self) -> Option<DateTimeAsMicroseconds> {
+        get_expires_at(self.created, self.expires, self.ttl)
+    }
+
+    pub fn get_partition_key(&self) -> Option<&str> {
+        self.partition_key.as_deref()
+    }
+
+    pub fn set_partition_key(&mut self, partition_key: Option<String>) {
+        self.partition_key = partition_key;
+    }
+
+    pub fn get_correlation_id(&self) -> Option<&str> {
+        self.correlation_id.as_deref()
+    }
+
+    pub fn set_correlation_id(&mut self, correlation_id: Option<String>) {
+        self.correlation_id = correlation_id;
+    }
+
+    pub fn get_content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    pub fn set_content_type(&mut self, content_type: Option<String>) {
+        self.content_type = content_type;
+    }
+
+    pub fn is_expired(&self, now: DateTimeAsMicroseconds) -> bool {
+        is_expired(self.expires_at(), now)
+    }
+
+    pub(super) fn map_data<TOther: MessageData>(
+        self,
+        map: impl FnOnce(TData) -> TOther,
+    ) -> GenericMessageProtobufModel<TOther> {
+        GenericMessageProtobufModel {
+            message_id: self.message_id,
+            created: self.created,
+            data: map(self.data),
+            headers: self.headers,
+            expires: self.expires,
+            ttl: self.ttl,
+            partition_key: self.partition_key,
+            correlation_id: self.correlation_id,
+            content_type: self.content_type,
+        }
+    }
+}
+
+// If both absolute expiration and TTL are set - whichever comes first wins
+pub(crate) fn get_expires_at(
+    created: i64,
+    expires: Option<i64>,
+    ttl: Option<i64>,
+) -> Option<DateTimeAsMicroseconds> {
+    let ttl_expires = ttl.map(|ttl| created.saturating_add(ttl.max(0)));
+
+    let result = match (expires, ttl_expires) {
+        (Some(expires), Some(ttl_expires)) => expires.min(ttl_expires),
+        (Some(expires), None) => expires,
+        (None, Some(ttl_expires)) => ttl_expires,
+        (None, None) => return None,
+    };
+
+    Some(DateTimeAsMicroseconds::new(result))
+}
+
+pub(crate) fn is_expired(
+    expires_at: Option<DateTimeAsMicroseconds>,
+    now: DateTimeAsMicroseconds,
+) -> bool {
+    match expires_at {
+        Some(expires_at) => now.unix_microseconds >= expires_at.unix_microseconds,
+        None => false,
+    }
 }
 
+pub(crate) fn micros_to_duration(micros: i64) -> Duration {
+    Duration::from_micros(micros.max(0) as u64)
+}
+
+pub(crate) fn duration_to_micros(duration: Duration) -> i64 {
+    i64::try_from(duration.as_micros()).unwrap_or(i64::MAX)
+}
+
+pub type MessagesProtobufModel = GenericMessagesProtobufModel<Vec<u8>>;
+
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct MessagesProtobufModel {
+pub struct GenericMessagesProtobufModel<TData: MessageData> {
     #[prost(message, repeated, tag = "1")]
-    pub messages: Vec<MessageProtobufModel>,
+    pub messages: Vec<GenericMessageProtobufModel<TData>>,
 }
 
 impl MessagesProtobufModel {
     pub fn parse(payload: &[u8]) -> Result<Self, DecodeError> {
         prost::Message::decode(payload)
     }
+}
 
+impl<TData: MessageData> GenericMessagesProtobufModel<TData> {
     pub fn serialize(&self, dest: &mut Vec<u8>) -> Result<(), EncodeError> {
         prost::Message::encode(self, dest)
     }
 }
 
 #[derive(Clone, PartialEq, ::prost::Message)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MessageMetaDataProtobufModel {
     #[prost(string, tag = "1")]
     pub key: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub value: ::prost::alloc::string::String,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_message(created: i64) -> MessageProtobufModel {
+        MessageProtobufModel::new(
+            1.into(),
+            DateTimeAsMicroseconds::new(created),
+            vec![],
+            vec![],
+        )
+    }
+
+    #[test]
+    fn test_message_without_expiration_never_expires() {
+        let msg = create_message(1_000_000);
+
+        assert!(msg.expires_at().is_none());
+        assert!(!msg.is_expired(DateTimeAsMicroseconds::new(i64::MAX)));
+    }
+
+    #[test]
+    fn test_expiration_by_ttl() {
+        let mut msg = create_message(1_000_000);
+        msg.set_ttl(Some(Duration::from_secs(1)));
+
+        assert_eq!(2_000_000, msg.expires_at().unwrap().unix_microseconds);
+        assert!(!msg.is_expired(DateTimeAsMicroseconds::new(1_999_999)));
+        assert!(msg.is_expired(DateTimeAsMicroseconds::new(2_000_000)));
+    }
+
+    #[test]
+    fn test_earliest_expiration_wins() {
+        let mut msg = create_message(1_000_000);
+        msg.set_ttl(Some(Duration::from_secs(10)));
+        msg.set_expires(Some(DateTimeAsMicroseconds::new(3_000_000)));
+
+        assert_eq!(3_000_000, msg.expires_at().unwrap().unix_microseconds);
+
+        msg.set_ttl(Some(Duration::from_secs(1)));
+
+        assert_eq!(2_000_000, msg.expires_at().unwrap().unix_microseconds);
+    }
+
+    #[test]
+    fn test_huge_ttl_does_not_wrap() {
+        let mut msg = create_message(1_000_000);
+        msg.set_ttl(Some(Duration::MAX));
+
+        assert_eq!(i64::MAX, msg.expires_at().unwrap().unix_microseconds);
+        assert!(!msg.is_expired(DateTimeAsMicroseconds::new(i64::MAX - 1)));
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::{BTreeMap, HashMap};
+
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use super::{MessageMetaDataProtobufModel, MessageProtobufModel};
+
+#[derive(Debug, Clone)]
+pub enum InvalidHeaderValue {
+    NotAnInteger(String),
+    NotAFloat(String),
+    NotADateTime(String),
+    NotAUuid(String),
+}
+
+impl MessageMetaDataProtobufModel {
+    pub fn new(key: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+}
+
+// Headers are allowed to have duplicate keys on the wire. The first one always wins:
+// get_header and map conversions return it, set_header overwrites it and drops the rest,
+// remove_header removes all of them.
+impl MessageProtobufModel {
+    pub fn get_header(&self, key: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|itm| itm.key == key)
+            .map(|itm| itm.value.as_str())
+    }
+
+    pub fn set_header(&mut self, key: &str, value: impl Into<String>) {
+        let value = value.into();
+
+        match self.headers.iter().position(|itm| itm.key == key) {
+            Some(index) => {
+                self.headers[index].value = value;
+
+                let mut i = index + 1;
+                while i < self.headers.len() {
+                    if self.headers[i].key == key {
+                        self.headers.remove(i);
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+            None => self
+                .headers
+                .push(MessageMetaDataProtobufModel::new(key, value)),
+        }
+    }
+
+    pub fn remove_header(&mut self, key: &str) -> Option<String> {
+        let index = self.headers.iter().position(|itm| itm.key == key)?;
+        let removed = self.headers.remove(index);
+        self.headers.retain(|itm| itm.key != key);
+        Some(removed.value)
+    }
+
+    pub fn headers_iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.headers
+            .iter()
+            .map(|itm| (itm.key.as_str(), itm.value.as_str()))
+    }
+
+    pub fn get_headers_as_hash_map(&self) -> HashMap<String, String> {
+        let mut result = HashMap::new();
+
+        for (key, value) in self.headers_iter() {
+            result
+                .entry(key.to_string())
+                .or_insert_with(|| value.to_string());
+        }
+
+        result
+    }
+
+    pub fn get_headers_as_b_tree_map(&self) -> BTreeMap<String, String> {
+        let mut result = BTreeMap::new();
+
+        for (key, value) in self.headers_iter() {
+            result
+                .entry(key.to_string())
+                .or_insert_with(|| value.to_string());
+        }
+
+        result
+    }
+
+    pub fn set_headers_from_map<K: Into<String>, V: Into<String>>(
+        &mut self,
+        src: impl IntoIterator<Item = (K, V)>,
+    ) {
+        self.headers = src
+            .into_iter()
+            .map(|(key, value)| MessageMetaDataProtobufModel::new(key, value))
+            .collect();
+    }
+
+    pub fn get_header_as_i64(&self, key: &str) -> Result<Option<i64>, InvalidHeaderValue> {
+        match self.get_header(key) {
+            Some(value) => match value.trim().parse() {
+                Ok(result) => Ok(Some(result)),
+                Err(_) => Err(InvalidHeaderValue::NotAnInteger(value.to_string())),
+            },
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_header_as_u64(&self, key: &str) -> Result<Option<u64>, InvalidHeaderValue> {
+        match self.get_header(key) {
+            Some(value) => match value.trim().parse() {
+                Ok(result) => Ok(Some(result)),
+                Err(_) => Err(InvalidHeaderValue::NotAnInteger(value.to_string())),
+            },
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_header_as_f64(&self, key: &str) -> Result<Option<f64>, InvalidHeaderValue> {
+        match self.get_header(key) {
+            Some(value) => match value.trim().parse() {
+                Ok(result) => Ok(Some(result)),
+                Err(_) => Err(InvalidHeaderValue::NotAFloat(value.to_string())),
+            },
+            None => Ok(None),
+        }
+    }
+
+    // Timestamps are expected in RFC3339 form, e.g. 2022-01-01T10:00:00.000000Z
+    pub fn get_header_as_date_time(
+        &self,
+        key: &str,
+    ) -> Result<Option<DateTimeAsMicroseconds>, InvalidHeaderValue> {
+        match self.get_header(key) {
+            Some(value) => match chrono::DateTime::parse_from_rfc3339(value.trim()) {
+                Ok(result) => Ok(Some(DateTimeAsMicroseconds::new(result.timestamp_micros()))),
+                Err(_) => Err(InvalidHeaderValue::NotADateTime(value.to_string())),
+            },
+            None => Ok(None),
+        }
+    }
+
+    pub fn get_header_as_uuid(&self, key: &str) -> Result<Option<uuid::Uuid>, InvalidHeaderValue> {
+        match self.get_header(key) {
+            Some(value) => match uuid::Uuid::parse_str(value.trim()) {
+                Ok(result) => Ok(Some(result)),
+                Err(_) => Err(InvalidHeaderValue::NotAUuid(value.to_string())),
+            },
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_message(headers: Vec<(&str, &str)>) -> MessageProtobufModel {
+        MessageProtobufModel::new(
+            1.into(),
+            DateTimeAsMicroseconds::new(0),
+            vec![],
+            headers
+                .into_iter()
+                .map(|(key, value)| MessageMetaDataProtobufModel::new(key, value))
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_duplicate_keys_policy() {
+        let mut msg = create_message(vec![("a", "1"), ("b", "2"), ("a", "3")]);
+
+        assert_eq!(Some("1"), msg.get_header("a"));
+        assert_eq!("1", msg.get_headers_as_hash_map()["a"]);
+        assert_eq!("1", msg.get_headers_as_b_tree_map()["a"]);
+
+        msg.set_header("a", "4");
+
+        let headers: Vec<(&str, &str)> = msg.headers_iter().collect();
+        assert_eq!(vec![("a", "4"), ("b", "2")], headers);
+
+        msg.headers
+            .push(MessageMetaDataProtobufModel::new("a", "5"));
+
+        assert_eq!(Some("4".to_string()), msg.remove_header("a"));
+        assert_eq!(None, msg.get_header("a"));
+        assert_eq!(1, msg.headers.len());
+
+        assert_eq!(None, msg.remove_header("a"));
+    }
+
+    #[test]
+    fn test_set_new_header_and_map_round_trip() {
+        let mut msg = create_message(vec![]);
+
+        msg.set_header("b", "2");
+        msg.set_header("a", "1");
+
+        let map = msg.get_headers_as_b_tree_map();
+
+        let mut other = create_message(vec![("c", "3")]);
+        other.set_headers_from_map(map);
+
+        let headers: Vec<(&str, &str)> = other.headers_iter().collect();
+        assert_eq!(vec![("a", "1"), ("b", "2")], headers);
+    }
+
+    #[test]
+    fn test_typed_getters() {
+        let msg = create_message(vec![
+            ("int", "-15"),
+            ("float", "1.5"),
+            ("date", "2022-01-01T10:00:00.000001Z"),
+            ("uuid", "67e55044-10b1-426f-9247-bb680e5fe0c8"),
+            ("bad", "abc"),
+        ]);
+
+        assert_eq!(Some(-15), msg.get_header_as_i64("int").unwrap());
+        assert_eq!(Some(1.5), msg.get_header_as_f64("float").unwrap());
+        assert_eq!(None, msg.get_header_as_i64("missing").unwrap());
+        assert!(msg.get_header_as_u64("int").is_err());
+
+        assert_eq!(
+            1_641_031_200_000_001,
+            msg.get_header_as_date_time("date")
+                .unwrap()
+                .unwrap()
+                .unix_microseconds
+        );
+
+        assert_eq!(
+            "67e55044-10b1-426f-9247-bb680e5fe0c8",
+            msg.get_header_as_uuid("uuid").unwrap().unwrap().to_string()
+        );
+
+        assert!(matches!(
+            msg.get_header_as_i64("bad"),
+            Err(InvalidHeaderValue::NotAnInteger(_))
+        ));
+        assert!(matches!(
+            msg.get_header_as_date_time("bad"),
+            Err(InvalidHeaderValue::NotADateTime(_))
+        ));
+        assert!(matches!(
+            msg.get_header_as_uuid("bad"),
+            Err(InvalidHeaderValue::NotAUuid(_))
+        ));
+    }
+}
@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+
+use my_service_bus_abstractions::{publisher::MessageToPublish, MessageId};
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use super::{MessageMetaDataProtobufModel, MessageProtobufModel};
+
+// MessageToPublish has no dedicated fields for these, so they travel as headers. Names are
+// reserved with the "x-msb-" prefix, so user headers like "content-type" are never taken for them.
+pub const PARTITION_KEY_HEADER: &str = "x-msb-partition-key";
+pub const CORRELATION_ID_HEADER: &str = "x-msb-correlation-id";
+pub const CONTENT_TYPE_HEADER: &str = "x-msb-content-type";
+
+impl MessageProtobufModel {
+    pub(super) fn from_headers_map(
+        message_id: MessageId,
+        created: DateTimeAsMicroseconds,
+        headers: Option<HashMap<String, String>>,
+        content: Vec<u8>,
+    ) -> Self {
+        let mut src_headers = headers.unwrap_or_default();
+
+        let partition_key = src_headers.remove(PARTITION_KEY_HEADER);
+        let correlation_id = src_headers.remove(CORRELATION_ID_HEADER);
+        let content_type = src_headers.remove(CONTENT_TYPE_HEADER);
+
+        let mut headers: Vec<MessageMetaDataProtobufModel> = src_headers
+            .into_iter()
+            .map(|(key, value)| MessageMetaDataProtobufModel::new(key, value))
+            .collect();
+
+        // HashMap does not keep the order, so we sort to make persisted payload deterministic
+        headers.sort_by(|a, b| a.key.cmp(&b.key));
+
+        let mut result = Self::new(message_id, created, content, headers);
+        result.set_partition_key(partition_key);
+        result.set_correlation_id(correlation_id);
+        result.set_content_type(content_type);
+        result
+    }
+
+    pub(super) fn get_headers_map(&self) -> HashMap<String, String> {
+        let mut result = self.get_headers_as_hash_map();
+
+        for (key, value) in [
+            (PARTITION_KEY_HEADER, self.get_partition_key()),
+            (CORRELATION_ID_HEADER, self.get_correlation_id()),
+            (CONTENT_TYPE_HEADER, self.get_content_type()),
+        ] {
+            if let Some(value) = value {
+                result.insert(key.to_string(), value.to_string());
+            }
+        }
+
+        result
+    }
+
+    fn get_headers_to_publish(&self) -> Option<HashMap<String, String>> {
+        let result = self.get_headers_map();
+
+        if result.is_empty() {
+            None
+        } else {
+            Some(result)
+        }
+    }
+}
+
+impl From<MessageProtobufModel> for MessageToPublish {
+    fn from(src: MessageProtobufModel) -> Self {
+        Self {
+            headers: src.get_headers_to_publish(),
+            content: src.data,
+        }
+    }
+}
+
+impl From<&MessageProtobufModel> for MessageToPublish {
+    fn from(src: &MessageProtobufModel) -> Self {
+        Self {
+            headers: src.get_headers_to_publish(),
+            content: src.data.clone(),
+        }
+    }
+}
+
+// Message id and created time are assigned by the service bus when message is published,
+// so they are not the part of MessageToPublish.
+impl From<(MessageId, DateTimeAsMicroseconds, MessageToPublish)> for MessageProtobufModel {
+    fn from(
+        (message_id, created, src): (MessageId, DateTimeAsMicroseconds, MessageToPublish),
+    ) -> Self {
+        Self::from_headers_map(message_id, created, src.headers, src.content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_message_to_publish() -> MessageToPublish {
+        let mut headers = HashMap::new();
+        headers.insert("b".to_string(), "2".to_string());
+        headers.insert("a".to_string(), "1".to_string());
+
+        MessageToPublish {
+            headers: Some(headers),
+            content: vec![1, 2, 3],
+        }
+    }
+
+    #[test]
+    fn test_from_message_to_publish_and_back() {
+        let model: MessageProtobufModel = (
+            15.into(),
+            DateTimeAsMicroseconds::new(1_000),
+            create_message_to_publish(),
+        )
+            .into();
+
+        assert_eq!(15, model.get_message_id().get_value());
+        assert_eq!(1_000, model.get_created().unix_microseconds);
+        assert_eq!(vec![1, 2, 3], model.data);
+
+        let headers: Vec<(&str, &str)> = model.headers_iter().collect();
+        assert_eq!(vec![("a", "1"), ("b", "2")], headers);
+
+        let result: MessageToPublish = model.into();
+
+        let src = create_message_to_publish();
+
+        assert_eq!(src.content, result.content);
+        assert_eq!(src.headers, result.headers);
+    }
+
+    #[test]
+    fn test_message_without_headers() {
+        let model: MessageProtobufModel = (
+            1.into(),
+            DateTimeAsMicroseconds::new(0),
+            MessageToPublish {
+                headers: None,
+                content: vec![],
+            },
+        )
+            .into();
+
+        assert!(model.headers.is_empty());
+        assert!(MessageToPublish::from(&model).headers.is_none());
+    }
+
+    #[test]
+    fn test_first_class_fields_travel_as_headers() {
+        let mut src = create_message_to_publish();
+        src.headers
+            .as_mut()
+            .unwrap()
+            .insert(PARTITION_KEY_HEADER.to_string(), "client-1".to_string());
+        src.headers.as_mut().unwrap().insert(
+            CONTENT_TYPE_HEADER.to_string(),
+            "application/json".to_string(),
+        );
+
+        let model: MessageProtobufModel = (1.into(), DateTimeAsMicroseconds::new(0), src).into();
+
+        assert_eq!(Some("client-1"), model.get_partition_key());
+        assert_eq!(Some("application/json"), model.get_content_type());
+        assert!(model.get_correlation_id().is_none());
+        assert_eq!(2, model.headers.len());
+
+        let result = MessageToPublish::from(&model).headers.unwrap();
+
+        assert_eq!(4, result.len());
+        assert_eq!("client-1", result[PARTITION_KEY_HEADER]);
+        assert_eq!("application/json", result[CONTENT_TYPE_HEADER]);
+    }
+
+    #[test]
+    fn test_user_headers_with_routing_names_survive_round_trip() {
+        let mut src = create_message_to_publish();
+        let headers = src.headers.as_mut().unwrap();
+
+        for key in ["partition-key", "correlation-id", "content-type"] {
+            headers.insert(key.to_string(), format!("user {}", key));
+        }
+
+        headers.insert(CONTENT_TYPE_HEADER.to_string(), "text/plain".to_string());
+
+        let model: MessageProtobufModel =
+            (1.into(), DateTimeAsMicroseconds::new(0), src.clone()).into();
+
+        assert_eq!(Some("text/plain"), model.get_content_type());
+        assert!(model.get_partition_key().is_none());
+        assert!(model.get_correlation_id().is_none());
+        assert_eq!(Some("user content-type"), model.get_header("content-type"));
+        assert_eq!(5, model.headers.len());
+
+        let result = MessageToPublish::from(&model);
+        assert_eq!(src.headers, result.headers);
+
+        let result: MessageProtobufModel =
+            (1.into(), DateTimeAsMicroseconds::new(0), result).into();
+        assert_eq!(model, result);
+    }
+}
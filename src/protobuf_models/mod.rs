@@ -1,2 +1,14 @@
 mod message;
+mod message_headers;
+mod message_to_publish;
+mod my_sb_message;
+mod proto_schema;
+mod shared_message;
+mod trace_context;
 pub use message::*;
+pub use message_headers::*;
+pub use message_to_publish::*;
+pub use my_sb_message::*;
+pub use proto_schema::*;
+pub use shared_message::*;
+pub use trace_context::*;
@@ -0,0 +1,137 @@
+use my_service_bus_abstractions::MySbMessage;
+use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+use super::{InvalidHeaderValue, MessageProtobufModel};
+
+// Delivered messages have no created time of their own, so it travels as a header
+// under the same reserved prefix as the routing fields
+pub const CREATED_HEADER: &str = "x-msb-created";
+
+#[derive(Debug, Clone)]
+pub enum InvalidMySbMessage {
+    InvalidCreatedHeader(InvalidHeaderValue),
+}
+
+impl From<InvalidHeaderValue> for InvalidMySbMessage {
+    fn from(src: InvalidHeaderValue) -> Self {
+        Self::InvalidCreatedHeader(src)
+    }
+}
+
+// Attempt number is a delivery detail which is not persisted, so it starts from 0
+impl From<MessageProtobufModel> for MySbMessage {
+    fn from(src: MessageProtobufModel) -> Self {
+        let mut headers = src.get_headers_map();
+
+        let created = chrono::DateTime::from_timestamp_micros(src.get_created().unix_microseconds)
+            .map(|itm| itm.to_rfc3339_opts(chrono::SecondsFormat::Micros, true))
+            .unwrap_or_else(|| src.get_created().unix_microseconds.to_string());
+
+        headers.insert(CREATED_HEADER.to_string(), created);
+
+        Self {
+            id: src.get_message_id(),
+            attempt_no: 0,
+            headers: Some(headers),
+            content: src.data,
+        }
+    }
+}
+
+// Messages delivered without the created header are treated as created at the moment of conversion
+impl TryFrom<MySbMessage> for MessageProtobufModel {
+    type Error = InvalidMySbMessage;
+
+    fn try_from(src: MySbMessage) -> Result<Self, Self::Error> {
+        let mut headers = src.headers.unwrap_or_default();
+
+        let created = match headers.remove(CREATED_HEADER) {
+            Some(created) => match chrono::DateTime::parse_from_rfc3339(created.trim()) {
+                Ok(result) => DateTimeAsMicroseconds::new(result.timestamp_micros()),
+                Err(_) => match created.trim().parse() {
+                    Ok(result) => DateTimeAsMicroseconds::new(result),
+                    Err(_) => return Err(InvalidHeaderValue::NotADateTime(created).into()),
+                },
+            },
+            None => DateTimeAsMicroseconds::now(),
+        };
+
+        Ok(Self::from_headers_map(
+            src.id,
+            created,
+            Some(headers),
+            src.content,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::protobuf_models::{MessageMetaDataProtobufModel, CORRELATION_ID_HEADER};
+
+    #[test]
+    fn test_delivered_message_round_trip() {
+        let mut src = MessageProtobufModel::new(
+            7.into(),
+            DateTimeAsMicroseconds::new(1_600_000_000_123_456),
+            vec![1, 2, 3],
+            vec![MessageMetaDataProtobufModel::new("a", "1")],
+        );
+        src.set_correlation_id(Some("request-1".to_string()));
+
+        let delivered: MySbMessage = src.clone().into();
+
+        assert_eq!(7, delivered.id.get_value());
+        assert_eq!(0, delivered.attempt_no);
+
+        let headers = delivered.headers.as_ref().unwrap();
+        assert_eq!("2020-09-13T12:26:40.123456Z", headers[CREATED_HEADER]);
+        assert_eq!("request-1", headers[CORRELATION_ID_HEADER]);
+
+        assert_eq!(src, MessageProtobufModel::try_from(delivered).unwrap());
+    }
+
+    #[test]
+    fn test_delivered_message_without_created_header() {
+        let mut headers = HashMap::new();
+        headers.insert("created".to_string(), "user value".to_string());
+
+        let mut delivered = MySbMessage {
+            id: 1.into(),
+            attempt_no: 0,
+            headers: Some(headers.clone()),
+            content: vec![],
+        };
+
+        let before = DateTimeAsMicroseconds::now().unix_microseconds;
+        let model = MessageProtobufModel::try_from(delivered.clone()).unwrap();
+        let after = DateTimeAsMicroseconds::now().unix_microseconds;
+
+        assert!((before..=after).contains(&model.get_created().unix_microseconds));
+        assert_eq!(Some("user value"), model.get_header("created"));
+
+        assert_eq!(
+            headers.into_iter().collect::<Vec<_>>(),
+            MySbMessage::from(model)
+                .headers
+                .unwrap()
+                .into_iter()
+                .filter(|(key, _)| key != CREATED_HEADER)
+                .collect::<Vec<_>>()
+        );
+
+        let mut headers = HashMap::new();
+        headers.insert(CREATED_HEADER.to_string(), "yesterday".to_string());
+        delivered.headers = Some(headers);
+
+        assert!(matches!(
+            MessageProtobufModel::try_from(delivered),
+            Err(InvalidMySbMessage::InvalidCreatedHeader(
+                InvalidHeaderValue::NotADateTime(_)
+            ))
+        ));
+    }
+}
@@ -0,0 +1,346 @@
+pub const PROTO_SCHEMA: &str = include_str!("../../proto/my_service_bus_persistence.proto");
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use prost::{
+        encoding::{DecodeContext, WireType},
+        Message,
+    };
+    use prost_types::FileDescriptorSet;
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::{
+        page_id::PageId,
+        page_presence::{
+            PagePresenceBitmap, PagePresenceBitmapEncoding, PagePresenceBitmapProtobufModel,
+        },
+        payload_validation::validate_protobuf_payload,
+        protobuf_models::{
+            MessageMetaDataProtobufModel, MessageProtobufModel, MessagesProtobufModel,
+            SharedMessageProtobufModel, SharedMessagesProtobufModel,
+        },
+    };
+
+    // Generated by build.rs from proto/my_service_bus_persistence.proto
+    mod compiled {
+        include!(concat!(env!("OUT_DIR"), "/my_service_bus_persistence.rs"));
+    }
+
+    const DESCRIPTOR_SET: &[u8] =
+        include_bytes!(concat!(env!("OUT_DIR"), "/my_service_bus_persistence.bin"));
+
+    const GOLDEN_MESSAGE: [u8; 26] = [
+        0x08, 0xa1, 0x8d, 0x06, // message_id = 100_001
+        0x10, 0x80, 0x80, 0x90, 0xbd, 0x90, 0xe6, 0xeb,
+        0x02, // created = 1_600_000_000_000_000
+        0x1a, 0x03, 0x61, 0x62, 0x63, // data = "abc"
+        0x22, 0x06, 0x0a, 0x01, 0x6b, 0x12, 0x01, 0x76, // headers = [k: v]
+    ];
+
+    const GOLDEN_EXPIRATION: [u8; 14] = [
+        0x28, 0x80, 0x8e, 0xde, 0xd9, 0x90, 0xe6, 0xeb,
+        0x02, // expires = 1_600_000_060_000_000
+        0x30, 0x80, 0x8e, 0xce, 0x1c, // ttl = 60_000_000
+    ];
+
+    const GOLDEN_ROUTING: [u8; 9] = [
+        0x3a, 0x01, 0x70, // partition_key = "p"
+        0x42, 0x01, 0x63, // correlation_id = "c"
+        0x4a, 0x01, 0x74, // content_type = "t"
+    ];
+
+    fn golden_model() -> MessageProtobufModel {
+        MessageProtobufModel::new(
+            100_001.into(),
+            DateTimeAsMicroseconds::new(1_600_000_000_000_000),
+            "abc".as_bytes().to_vec(),
+            vec![MessageMetaDataProtobufModel::new("k", "v")],
+        )
+    }
+
+    fn golden_model_with_all_fields() -> MessageProtobufModel {
+        let mut result = golden_model();
+        result.set_expires(Some(DateTimeAsMicroseconds::new(1_600_000_060_000_000)));
+        result.set_ttl(Some(std::time::Duration::from_secs(60)));
+        result.set_partition_key(Some("p".to_string()));
+        result.set_correlation_id(Some("c".to_string()));
+        result.set_content_type(Some("t".to_string()));
+        result
+    }
+
+    fn golden_message_with_all_fields() -> Vec<u8> {
+        let mut result = GOLDEN_MESSAGE.to_vec();
+        result.extend_from_slice(&GOLDEN_EXPIRATION);
+        result.extend_from_slice(&GOLDEN_ROUTING);
+        result
+    }
+
+    fn golden_messages() -> Vec<u8> {
+        let mut result = vec![0x0a, GOLDEN_MESSAGE.len() as u8];
+        result.extend_from_slice(&GOLDEN_MESSAGE);
+        result
+    }
+
+    #[test]
+    fn test_message_golden_bytes() {
+        let mut payload = Vec::new();
+        golden_model().serialize(&mut payload).unwrap();
+
+        assert_eq!(GOLDEN_MESSAGE.to_vec(), payload);
+
+        let model = MessageProtobufModel::parse(&GOLDEN_MESSAGE).unwrap();
+        assert_eq!(golden_model(), model);
+    }
+
+    #[test]
+    fn test_message_with_all_fields_golden_bytes() {
+        let mut payload = Vec::new();
+        golden_model_with_all_fields()
+            .serialize(&mut payload)
+            .unwrap();
+
+        assert_eq!(golden_message_with_all_fields(), payload);
+
+        let model =
+            MessageProtobufModel::parse(golden_message_with_all_fields().as_slice()).unwrap();
+        assert_eq!(golden_model_with_all_fields(), model);
+    }
+
+    #[test]
+    fn test_old_payload_has_optional_fields_empty() {
+        let model = MessageProtobufModel::parse(&GOLDEN_MESSAGE).unwrap();
+
+        assert!(model.get_expires().is_none());
+        assert!(model.get_ttl().is_none());
+        assert!(model.get_partition_key().is_none());
+        assert!(model.get_correlation_id().is_none());
+        assert!(model.get_content_type().is_none());
+    }
+
+    #[test]
+    fn test_messages_golden_bytes() {
+        let messages = MessagesProtobufModel {
+            messages: vec![golden_model()],
+        };
+
+        let mut payload = Vec::new();
+        messages.serialize(&mut payload).unwrap();
+
+        assert_eq!(golden_messages(), payload);
+
+        let model = MessagesProtobufModel::parse(golden_messages().as_slice()).unwrap();
+        assert_eq!(messages, model);
+    }
+
+    #[test]
+    fn test_shared_message_golden_bytes() {
+        let model =
+            SharedMessageProtobufModel::parse(golden_message_with_all_fields().into()).unwrap();
+
+        let mut payload = Vec::new();
+        model.serialize(&mut payload).unwrap();
+
+        assert_eq!(golden_message_with_all_fields(), payload);
+        assert_eq!(golden_model_with_all_fields(), model.into());
+    }
+
+    // Every struct is destructured and built without `..`, so a field added on either side
+    // does not compile until it is mapped here
+    fn to_compiled_message(src: MessageProtobufModel) -> compiled::MessageProtobufModel {
+        let MessageProtobufModel {
+            message_id,
+            created,
+            data,
+            headers,
+            expires,
+            ttl,
+            partition_key,
+            correlation_id,
+            content_type,
+        } = src;
+
+        compiled::MessageProtobufModel {
+            message_id,
+            created,
+            data,
+            headers: headers.into_iter().map(to_compiled_header).collect(),
+            expires,
+            ttl,
+            partition_key,
+            correlation_id,
+            content_type,
+        }
+    }
+
+    fn to_compiled_header(
+        src: MessageMetaDataProtobufModel,
+    ) -> compiled::MessageMetaDataProtobufModel {
+        let MessageMetaDataProtobufModel { key, value } = src;
+        compiled::MessageMetaDataProtobufModel { key, value }
+    }
+
+    fn to_compiled_messages(src: MessagesProtobufModel) -> compiled::MessagesProtobufModel {
+        let MessagesProtobufModel { messages } = src;
+
+        compiled::MessagesProtobufModel {
+            messages: messages.into_iter().map(to_compiled_message).collect(),
+        }
+    }
+
+    fn to_compiled_bitmap(
+        src: PagePresenceBitmapProtobufModel,
+    ) -> compiled::PagePresenceBitmapProtobufModel {
+        let PagePresenceBitmapProtobufModel {
+            page_id,
+            messages_in_page,
+            bits,
+            runs,
+        } = src;
+
+        compiled::PagePresenceBitmapProtobufModel {
+            page_id,
+            messages_in_page,
+            bits,
+            runs,
+        }
+    }
+
+    fn golden_bitmaps() -> Vec<PagePresenceBitmapProtobufModel> {
+        let mut bitmap = PagePresenceBitmap::new(PageId::new(1));
+        bitmap.insert(100_001.into());
+
+        vec![
+            bitmap.to_protobuf_model(PagePresenceBitmapEncoding::Raw),
+            bitmap.to_protobuf_model(PagePresenceBitmapEncoding::RunLength),
+        ]
+    }
+
+    fn encode(model: &impl prost::Message) -> Vec<u8> {
+        let mut result = Vec::new();
+        model.encode(&mut result).unwrap();
+        result
+    }
+
+    // Hand written model and the one generated from the proto file must produce the same bytes
+    // and decode each other's bytes
+    fn check_model<TModel: prost::Message + Default + PartialEq + std::fmt::Debug>(
+        model: &impl prost::Message,
+        compiled: &TModel,
+    ) {
+        let payload = encode(model);
+
+        assert_eq!(encode(compiled), payload);
+        assert_eq!(compiled, &TModel::decode(payload.as_slice()).unwrap());
+    }
+
+    #[test]
+    fn test_rust_models_match_compiled_proto() {
+        let model = golden_model_with_all_fields();
+
+        check_model(&model, &to_compiled_message(model.clone()));
+        check_model(
+            &SharedMessageProtobufModel::from(model.clone()),
+            &to_compiled_message(model.clone()),
+        );
+
+        let messages = MessagesProtobufModel {
+            messages: vec![model.clone(), golden_model()],
+        };
+
+        check_model(&messages, &to_compiled_messages(messages.clone()));
+        check_model(
+            &SharedMessagesProtobufModel {
+                messages: messages.messages.iter().cloned().map(Into::into).collect(),
+            },
+            &to_compiled_messages(messages),
+        );
+
+        for bitmap in golden_bitmaps() {
+            check_model(&bitmap, &to_compiled_bitmap(bitmap.clone()));
+        }
+
+        let compiled = compiled::MessageProtobufModel::decode(GOLDEN_MESSAGE.as_slice()).unwrap();
+        assert_eq!(
+            golden_model(),
+            MessageProtobufModel::parse(&encode(&compiled)).unwrap()
+        );
+    }
+
+    // Every field of the descriptor is set, so all the tags of the message have to be on the wire
+    // and match the declared types
+    fn check_descriptor(descriptor_set: &FileDescriptorSet, message_name: &str, payload: &[u8]) {
+        let full_name = format!(".my_service_bus_persistence.{}", message_name);
+
+        validate_protobuf_payload(&descriptor_set.file, full_name.as_str(), payload)
+            .unwrap_or_else(|err| panic!("{}: {}", message_name, err));
+
+        let descriptor = descriptor_set.file[0]
+            .message_type
+            .iter()
+            .find(|itm| itm.name() == message_name)
+            .unwrap();
+
+        let mut tags = BTreeSet::new();
+        let mut payload = payload;
+
+        while !payload.is_empty() {
+            let key = prost::encoding::decode_varint(&mut payload).unwrap();
+            let wire_type = WireType::try_from(key & 0x07).unwrap();
+            prost::encoding::skip_field(
+                wire_type,
+                (key >> 3) as u32,
+                &mut payload,
+                DecodeContext::default(),
+            )
+            .unwrap();
+
+            tags.insert((key >> 3) as i32);
+        }
+
+        assert_eq!(
+            descriptor
+                .field
+                .iter()
+                .map(|itm| itm.number())
+                .collect::<BTreeSet<_>>(),
+            tags,
+            "Tags of {}",
+            message_name
+        );
+    }
+
+    #[test]
+    fn test_rust_models_match_proto_descriptors() {
+        let descriptor_set = FileDescriptorSet::decode(DESCRIPTOR_SET).unwrap();
+
+        let model = golden_model_with_all_fields();
+        check_descriptor(&descriptor_set, "MessageProtobufModel", &encode(&model));
+        check_descriptor(
+            &descriptor_set,
+            "MessageProtobufModel",
+            &encode(&SharedMessageProtobufModel::from(model.clone())),
+        );
+        check_descriptor(
+            &descriptor_set,
+            "MessageMetaDataProtobufModel",
+            &encode(&model.headers[0]),
+        );
+        check_descriptor(
+            &descriptor_set,
+            "MessagesProtobufModel",
+            &encode(&MessagesProtobufModel {
+                messages: vec![model],
+            }),
+        );
+
+        let mut bitmap = golden_bitmaps().remove(0);
+        bitmap.runs = vec![1];
+        check_descriptor(
+            &descriptor_set,
+            "PagePresenceBitmapProtobufModel",
+            &encode(&bitmap),
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use bytes::Bytes;
+use prost::DecodeError;
+
+use super::{GenericMessageProtobufModel, GenericMessagesProtobufModel, MessageProtobufModel};
+
+// Decoding from Bytes makes data a slice of the source buffer so all the messages of a page share
+// one allocation.
+pub type SharedMessageProtobufModel = GenericMessageProtobufModel<Bytes>;
+
+impl SharedMessageProtobufModel {
+    pub fn parse(payload: Bytes) -> Result<Self, DecodeError> {
+        prost::Message::decode(payload)
+    }
+}
+
+impl From<MessageProtobufModel> for SharedMessageProtobufModel {
+    fn from(src: MessageProtobufModel) -> Self {
+        src.map_data(Bytes::from)
+    }
+}
+
+impl From<SharedMessageProtobufModel> for MessageProtobufModel {
+    fn from(src: SharedMessageProtobufModel) -> Self {
+        src.map_data(|data| data.to_vec())
+    }
+}
+
+pub type SharedMessagesProtobufModel = GenericMessagesProtobufModel<Bytes>;
+
+impl SharedMessagesProtobufModel {
+    pub fn parse(payload: Bytes) -> Result<Self, DecodeError> {
+        prost::Message::decode(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::protobuf_models::{MessageMetaDataProtobufModel, MessagesProtobufModel};
+
+    use super::*;
+
+    #[test]
+    fn test_shared_messages_point_to_the_same_buffer() {
+        let messages = MessagesProtobufModel {
+            messages: vec![
+                MessageProtobufModel::new(
+                    1.into(),
+                    DateTimeAsMicroseconds::new(1),
+                    vec![1u8; 16],
+                    vec![],
+                ),
+                MessageProtobufModel::new(
+                    2.into(),
+                    DateTimeAsMicroseconds::new(2),
+                    vec![2u8; 16],
+                    vec![MessageMetaDataProtobufModel {
+                        key: "key".to_string(),
+                        value: "value".to_string(),
+                    }],
+                ),
+            ],
+        };
+
+        let mut payload = Vec::new();
+        messages.serialize(&mut payload).unwrap();
+
+        let payload = Bytes::from(payload);
+        let buffer_range = payload.as_ptr_range();
+
+        let shared = SharedMessagesProtobufModel::parse(payload.clone()).unwrap();
+
+        assert_eq!(2, shared.messages.len());
+
+        for (src, shared) in messages.messages.iter().zip(shared.messages.iter()) {
+            assert_eq!(
+                src.get_message_id().get_value(),
+                shared.get_message_id().get_value()
+            );
+            assert_eq!(
+                src.get_created().unix_microseconds,
+                shared.get_created().unix_microseconds
+            );
+            assert_eq!(src.data.as_slice(), &shared.data[..]);
+            assert_eq!(src.headers, shared.headers);
+
+            assert!(buffer_range.contains(&shared.data.as_ptr()));
+        }
+
+        let restored: MessageProtobufModel = shared.messages[1].clone().into();
+        assert_eq!(messages.messages[1], restored);
+    }
+
+    #[test]
+    fn test_shared_and_owned_models_have_the_same_wire_format() {
+        let mut shared = SharedMessageProtobufModel::new(
+            15.into(),
+            DateTimeAsMicroseconds::new(1_000_000),
+            Bytes::from_static(&[1, 2, 3]),
+            vec![MessageMetaDataProtobufModel {
+                key: "key".to_string(),
+                value: "value".to_string(),
+            }],
+        );
+        shared.set_expires(Some(DateTimeAsMicroseconds::new(5_000_000)));
+        shared.set_ttl(Some(Duration::from_secs(2)));
+        shared.set_partition_key(Some("partition".to_string()));
+        shared.set_correlation_id(Some("correlation".to_string()));
+        shared.set_content_type(Some("application/json".to_string()));
+
+        let mut src = MessageProtobufModel::new(
+            15.into(),
+            DateTimeAsMicroseconds::new(1_000_000),
+            vec![1, 2, 3],
+            shared.headers.clone(),
+        );
+        src.set_expires(shared.get_expires());
+        src.set_ttl(shared.get_ttl());
+        src.set_partition_key(shared.get_partition_key().map(|itm| itm.to_string()));
+        src.set_correlation_id(shared.get_correlation_id().map(|itm| itm.to_string()));
+        src.set_content_type(shared.get_content_type().map(|itm| itm.to_string()));
+
+        assert_eq!(shared, SharedMessageProtobufModel::from(src.clone()));
+
+        let mut owned_payload = Vec::new();
+        src.serialize(&mut owned_payload).unwrap();
+
+        let shared = SharedMessageProtobufModel::parse(owned_payload.clone().into()).unwrap();
+
+        let mut shared_payload = Vec::new();
+        shared.serialize(&mut shared_payload).unwrap();
+
+        assert_eq!(owned_payload, shared_payload);
+
+        let mut converted_payload = Vec::new();
+        SharedMessageProtobufModel::from(src.clone())
+            .serialize(&mut converted_payload)
+            .unwrap();
+
+        assert_eq!(owned_payload, converted_payload);
+        assert_eq!(src, MessageProtobufModel::parse(&shared_payload).unwrap());
+    }
+
+    #[test]
+    fn test_conversions_keep_raw_values() {
+        let mut src =
+            MessageProtobufModel::new(1.into(), DateTimeAsMicroseconds::new(1_000), vec![], vec![]);
+        src.ttl = Some(-5);
+
+        let shared = SharedMessageProtobufModel::from(src.clone());
+        assert_eq!(Some(-5), shared.ttl);
+
+        assert_eq!(src, MessageProtobufModel::from(shared));
+    }
+}
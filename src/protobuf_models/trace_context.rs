@@ -0,0 +1,375 @@
+use super::MessageProtobufModel;
+
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+const TRACEPARENT_LEN: usize = 55;
+const MAX_TRACESTATE_MEMBERS: usize = 32;
+const MAX_TRACESTATE_KEY_LEN: usize = 256;
+const MAX_TRACESTATE_TENANT_ID_LEN: usize = 241;
+const MAX_TRACESTATE_SYSTEM_ID_LEN: usize = 14;
+const MAX_TRACESTATE_VALUE_LEN: usize = 256;
+const SAMPLED_FLAG: u8 = 0x01;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InvalidTraceContext {
+    InvalidTraceParent(String),
+    InvalidTraceState(String),
+}
+
+// https://www.w3.org/TR/trace-context/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    pub version: u8,
+    pub trace_id: [u8; 16],
+    pub parent_id: [u8; 8],
+    pub flags: u8,
+    pub trace_state: Option<String>,
+}
+
+impl TraceContext {
+    pub fn new(trace_id: [u8; 16], parent_id: [u8; 8], sampled: bool) -> Self {
+        Self {
+            version: 0,
+            trace_id,
+            parent_id,
+            flags: if sampled { SAMPLED_FLAG } else { 0 },
+            trace_state: None,
+        }
+    }
+
+    pub fn is_sampled(&self) -> bool {
+        self.flags & SAMPLED_FLAG == SAMPLED_FLAG
+    }
+
+    pub fn with_parent_id(&self, parent_id: [u8; 8]) -> Self {
+        Self {
+            version: self.version,
+            trace_id: self.trace_id,
+            parent_id,
+            flags: self.flags,
+            trace_state: self.trace_state.clone(),
+        }
+    }
+
+    pub fn get_trace_id_as_hex(&self) -> String {
+        to_hex(&self.trace_id)
+    }
+
+    pub fn get_parent_id_as_hex(&self) -> String {
+        to_hex(&self.parent_id)
+    }
+
+    pub fn to_traceparent(&self) -> String {
+        format!(
+            "{:02x}-{}-{}-{:02x}",
+            self.version,
+            self.get_trace_id_as_hex(),
+            self.get_parent_id_as_hex(),
+            self.flags
+        )
+    }
+
+    pub fn parse(traceparent: &str, tracestate: Option<&str>) -> Result<Self, InvalidTraceContext> {
+        let invalid = || InvalidTraceContext::InvalidTraceParent(traceparent.to_string());
+
+        let traceparent = traceparent.trim();
+
+        if traceparent.len() < TRACEPARENT_LEN || !traceparent.is_ascii() {
+            return Err(invalid());
+        }
+
+        let version = from_hex::<1>(&traceparent[0..2]).ok_or_else(invalid)?[0];
+
+        // Version ff is forbidden. Future versions may add fields after the flags
+        if version == 0xff
+            || (version == 0 && traceparent.len() != TRACEPARENT_LEN)
+            || (traceparent.len() > TRACEPARENT_LEN && &traceparent[55..56] != "-")
+        {
+            return Err(invalid());
+        }
+
+        if &traceparent[2..3] != "-" || &traceparent[35..36] != "-" || &traceparent[52..53] != "-" {
+            return Err(invalid());
+        }
+
+        let trace_id = from_hex::<16>(&traceparent[3..35]).ok_or_else(invalid)?;
+        let parent_id = from_hex::<8>(&traceparent[36..52]).ok_or_else(invalid)?;
+        let flags = from_hex::<1>(&traceparent[53..55]).ok_or_else(invalid)?[0];
+
+        if trace_id.iter().all(|b| *b == 0) || parent_id.iter().all(|b| *b == 0) {
+            return Err(invalid());
+        }
+
+        let trace_state = match tracestate {
+            Some(tracestate) => {
+                validate_trace_state(tracestate)?;
+                Some(tracestate.to_string())
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            version,
+            trace_id,
+            parent_id,
+            flags,
+            trace_state,
+        })
+    }
+}
+
+impl MessageProtobufModel {
+    pub fn inject_trace_context(&mut self, trace_context: &TraceContext) {
+        self.set_header(TRACEPARENT_HEADER, trace_context.to_traceparent());
+
+        match &trace_context.trace_state {
+            Some(trace_state) => self.set_header(TRACESTATE_HEADER, trace_state.as_str()),
+            None => {
+                self.remove_header(TRACESTATE_HEADER);
+            }
+        }
+    }
+
+    pub fn extract_trace_context(&self) -> Result<Option<TraceContext>, InvalidTraceContext> {
+        let traceparent = match self.get_header(TRACEPARENT_HEADER) {
+            Some(traceparent) => traceparent,
+            None => return Ok(None),
+        };
+
+        let result = TraceContext::parse(traceparent, self.get_header(TRACESTATE_HEADER))?;
+
+        Ok(Some(result))
+    }
+}
+
+fn validate_trace_state(src: &str) -> Result<(), InvalidTraceContext> {
+    let mut members = 0;
+
+    for member in src.split(',') {
+        let member = member.trim_matches(|c| c == ' ' || c == '\t');
+
+        if member.is_empty() {
+            continue;
+        }
+
+        members += 1;
+
+        let valid = match member.split_once('=') {
+            Some((key, value)) => {
+                is_valid_trace_state_key(key) && is_valid_trace_state_value(value)
+            }
+            None => false,
+        };
+
+        if !valid || members > MAX_TRACESTATE_MEMBERS {
+            return Err(InvalidTraceContext::InvalidTraceState(src.to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+// key = simple-key / tenant-id "@" system-id
+fn is_valid_trace_state_key(key: &str) -> bool {
+    match key.split_once('@') {
+        Some((tenant_id, system_id)) => {
+            is_valid_trace_state_key_part(tenant_id, MAX_TRACESTATE_TENANT_ID_LEN, true)
+                && is_valid_trace_state_key_part(system_id, MAX_TRACESTATE_SYSTEM_ID_LEN, false)
+        }
+        None => is_valid_trace_state_key_part(key, MAX_TRACESTATE_KEY_LEN, false),
+    }
+}
+
+fn is_valid_trace_state_key_part(src: &str, max_len: usize, may_start_with_digit: bool) -> bool {
+    let first = match src.bytes().next() {
+        Some(first) => first,
+        None => return false,
+    };
+
+    if !(first.is_ascii_lowercase() || (may_start_with_digit && first.is_ascii_digit())) {
+        return false;
+    }
+
+    src.len() <= max_len
+        && src.bytes().all(|c| {
+            c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, b'_' | b'-' | b'*' | b'/')
+        })
+}
+
+// Printable ascii except ',' and '=', the last char can not be a space
+fn is_valid_trace_state_value(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_TRACESTATE_VALUE_LEN
+        && !value.ends_with(' ')
+        && value
+            .bytes()
+            .all(|c| (0x20..=0x7e).contains(&c) && c != b',' && c != b'=')
+}
+
+fn to_hex(src: &[u8]) -> String {
+    let mut result = String::with_capacity(src.len() * 2);
+
+    for b in src {
+        result.push_str(format!("{:02x}", b).as_str());
+    }
+
+    result
+}
+
+// Only lower case hex is allowed by the spec
+fn from_hex<const N: usize>(src: &str) -> Option<[u8; N]> {
+    if src.len() != N * 2 {
+        return None;
+    }
+
+    let mut result = [0u8; N];
+
+    for (index, chunk) in src.as_bytes().chunks(2).enumerate() {
+        result[index] = (hex_digit(chunk[0])? << 4) | hex_digit(chunk[1])?;
+    }
+
+    Some(result)
+}
+
+fn hex_digit(c: u8) -> Option<u8> {
+    match c {
+        b'0'..=b'9' => Some(c - b'0'),
+        b'a'..=b'f' => Some(c - b'a' + 10),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::*;
+
+    const TRACEPARENT: &str = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+
+    fn create_message() -> MessageProtobufModel {
+        MessageProtobufModel::new(1.into(), DateTimeAsMicroseconds::new(0), vec![], vec![])
+    }
+
+    #[test]
+    fn test_parse_and_format() {
+        let trace_context = TraceContext::parse(TRACEPARENT, Some("congo=t61rcWkgMzE")).unwrap();
+
+        assert_eq!(
+            "4bf92f3577b34da6a3ce929d0e0e4736",
+            trace_context.get_trace_id_as_hex()
+        );
+        assert_eq!("00f067aa0ba902b7", trace_context.get_parent_id_as_hex());
+        assert!(trace_context.is_sampled());
+        assert_eq!(TRACEPARENT, trace_context.to_traceparent());
+    }
+
+    #[test]
+    fn test_invalid_traceparent() {
+        for src in [
+            "",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7",
+            "00-4BF92F3577B34DA6A3CE929D0E0E4736-00f067aa0ba902b7-01",
+            "00-00000000000000000000000000000000-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-0000000000000000-01",
+            "ff-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra",
+            "00_4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+        ] {
+            assert!(
+                matches!(
+                    TraceContext::parse(src, None),
+                    Err(InvalidTraceContext::InvalidTraceParent(_))
+                ),
+                "{}",
+                src
+            );
+        }
+    }
+
+    #[test]
+    fn test_future_version_may_have_extra_fields() {
+        let src = "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01-extra";
+
+        let trace_context = TraceContext::parse(src, None).unwrap();
+
+        assert_eq!(1, trace_context.version);
+        assert_eq!(
+            "01-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01",
+            trace_context.to_traceparent()
+        );
+    }
+
+    #[test]
+    fn test_invalid_tracestate() {
+        assert!(matches!(
+            TraceContext::parse(TRACEPARENT, Some("congo")),
+            Err(InvalidTraceContext::InvalidTraceState(_))
+        ));
+
+        assert!(TraceContext::parse(TRACEPARENT, Some("a=1, b=2,,")).is_ok());
+
+        for tracestate in [
+            "1a=value",
+            "_a=value",
+            "a@=value",
+            "@a=value",
+            "tenant@1system=value",
+            "tenant@systemistoolong=value",
+            "a@b@c=value",
+        ] {
+            assert!(
+                TraceContext::parse(TRACEPARENT, Some(tracestate)).is_err(),
+                "{}",
+                tracestate
+            );
+        }
+
+        assert!(TraceContext::parse(TRACEPARENT, Some("1tenant@system=va lue")).is_ok());
+
+        assert!(!is_valid_trace_state_value("value "));
+
+        let key = "a".repeat(MAX_TRACESTATE_KEY_LEN);
+        let value = "v".repeat(MAX_TRACESTATE_VALUE_LEN);
+
+        assert!(
+            TraceContext::parse(TRACEPARENT, Some(format!("{}={}", key, value).as_str())).is_ok()
+        );
+        assert!(
+            TraceContext::parse(TRACEPARENT, Some(format!("{}a={}", key, value).as_str())).is_err()
+        );
+        assert!(
+            TraceContext::parse(TRACEPARENT, Some(format!("{}={}v", key, value).as_str())).is_err()
+        );
+    }
+
+    #[test]
+    fn test_inject_and_extract() {
+        let mut msg = create_message();
+
+        assert!(msg.extract_trace_context().unwrap().is_none());
+
+        let mut trace_context = TraceContext::new([1; 16], [2; 8], true);
+        trace_context.trace_state = Some("vendor=value".to_string());
+
+        msg.inject_trace_context(&trace_context);
+
+        assert_eq!(
+            Some("00-01010101010101010101010101010101-0202020202020202-01"),
+            msg.get_header(TRACEPARENT_HEADER)
+        );
+        assert_eq!(trace_context, msg.extract_trace_context().unwrap().unwrap());
+
+        let mut child = trace_context.with_parent_id([3; 8]);
+        child.trace_state = None;
+
+        msg.inject_trace_context(&child);
+
+        assert_eq!(
+            Some("00-01010101010101010101010101010101-0303030303030303-01"),
+            msg.get_header(TRACEPARENT_HEADER)
+        );
+        assert!(msg.get_header(TRACESTATE_HEADER).is_none());
+    }
+}
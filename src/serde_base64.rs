@@ -0,0 +1,49 @@
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serializer};
+
+pub fn serialize<S: Serializer>(data: &impl AsRef<[u8]>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(
+        base64::engine::general_purpose::STANDARD
+            .encode(data.as_ref())
+            .as_str(),
+    )
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>, T: From<Vec<u8>>>(
+    deserializer: D,
+) -> Result<T, D::Error> {
+    let src = String::deserialize(deserializer)?;
+
+    let result = base64::engine::general_purpose::STANDARD
+        .decode(src.as_str())
+        .map_err(serde::de::Error::custom)?;
+
+    Ok(result.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use crate::protobuf_models::{MessageMetaDataProtobufModel, MessageProtobufModel};
+
+    #[test]
+    fn test_message_data_is_base64() {
+        let mut msg = MessageProtobufModel::new(
+            5.into(),
+            DateTimeAsMicroseconds::new(1_000),
+            vec![0, 1, 2, 255],
+            vec![MessageMetaDataProtobufModel::new("key", "value")],
+        );
+        msg.set_partition_key(Some("partition".to_string()));
+
+        let json = serde_json::to_string(&msg).unwrap();
+
+        assert!(json.contains("\"data\":\"AAEC/w==\""));
+        assert!(json.contains("\"headers\":[{\"key\":\"key\",\"value\":\"value\"}]"));
+
+        let result: MessageProtobufModel = serde_json::from_str(json.as_str()).unwrap();
+
+        assert_eq!(msg, result);
+    }
+}
@@ -0,0 +1,197 @@
+use std::sync::atomic::{fence, AtomicUsize, Ordering};
+
+use super::SizeAndAmount;
+
+// Can be updated and read from several threads. Size and amount are guarded by a sequence
+// number (seqlock): writers take turns by making it odd, readers retry until they see the same
+// even number before and after reading, so a snapshot never mixes two updates.
+// Removing more than there is saturates at zero.
+#[derive(Debug, Default)]
+pub struct AtomicSizeAndAmount {
+    sequence: AtomicUsize,
+    size: AtomicUsize,
+    amount: AtomicUsize,
+}
+
+impl AtomicSizeAndAmount {
+    pub fn new() -> Self {
+        Self {
+            sequence: AtomicUsize::new(0),
+            size: AtomicUsize::new(0),
+            amount: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn added(&self, size: usize) {
+        self.added_page(&SizeAndAmount { size, amount: 1 });
+    }
+
+    pub fn removed(&self, size: usize) {
+        self.removed_page(&SizeAndAmount { size, amount: 1 });
+    }
+
+    pub fn added_page(&self, other: &SizeAndAmount) {
+        self.update(|current| current.saturating_add(other));
+    }
+
+    pub fn removed_page(&self, other: &SizeAndAmount) {
+        self.update(|current| current.saturating_sub(other));
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.size.load(Ordering::Relaxed)
+    }
+
+    pub fn get_amount(&self) -> usize {
+        self.amount.load(Ordering::Relaxed)
+    }
+
+    pub fn get_snapshot(&self) -> SizeAndAmount {
+        loop {
+            let sequence = self.sequence.load(Ordering::Acquire);
+
+            if sequence & 1 == 1 {
+                std::hint::spin_loop();
+                continue;
+            }
+
+            let result = SizeAndAmount {
+                size: self.size.load(Ordering::Relaxed),
+                amount: self.amount.load(Ordering::Relaxed),
+            };
+
+            fence(Ordering::Acquire);
+
+            if self.sequence.load(Ordering::Relaxed) == sequence {
+                return result;
+            }
+        }
+    }
+
+    pub fn set(&self, value: &SizeAndAmount) {
+        self.update(|_| *value);
+    }
+
+    fn update(&self, update: impl FnOnce(SizeAndAmount) -> SizeAndAmount) {
+        let sequence = loop {
+            let sequence = self.sequence.load(Ordering::Relaxed);
+
+            if sequence & 1 == 0
+                && self
+                    .sequence
+                    .compare_exchange_weak(
+                        sequence,
+                        sequence.wrapping_add(1),
+                        Ordering::Acquire,
+                        Ordering::Relaxed,
+                    )
+                    .is_ok()
+            {
+                break sequence;
+            }
+
+            std::hint::spin_loop();
+        };
+
+        let result = update(SizeAndAmount {
+            size: self.size.load(Ordering::Relaxed),
+            amount: self.amount.load(Ordering::Relaxed),
+        });
+
+        self.size.store(result.size, Ordering::Relaxed);
+        self.amount.store(result.amount, Ordering::Relaxed);
+
+        self.sequence
+            .store(sequence.wrapping_add(2), Ordering::Release);
+    }
+}
+
+impl From<SizeAndAmount> for AtomicSizeAndAmount {
+    fn from(src: SizeAndAmount) -> Self {
+        Self {
+            sequence: AtomicUsize::new(0),
+            size: AtomicUsize::new(src.size),
+            amount: AtomicUsize::new(src.amount),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+
+    #[test]
+    fn test_added_and_removed() {
+        let value = AtomicSizeAndAmount::new();
+
+        value.added(10);
+        value.added(5);
+        value.removed(10);
+
+        assert_eq!(SizeAndAmount { size: 5, amount: 1 }, value.get_snapshot());
+
+        value.removed_page(&SizeAndAmount {
+            size: 100,
+            amount: 100,
+        });
+
+        assert_eq!(SizeAndAmount::new(), value.get_snapshot());
+    }
+
+    #[test]
+    fn test_concurrent_updates() {
+        let value = Arc::new(AtomicSizeAndAmount::from(SizeAndAmount {
+            size: 1_000,
+            amount: 10,
+        }));
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let value = value.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..1_000 {
+                        value.added(3);
+                        value.removed(1);
+                    }
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(1_000 + 4 * 1_000 * 2, value.get_size());
+        assert_eq!(10, value.get_amount());
+    }
+
+    #[test]
+    fn test_snapshot_is_consistent_during_updates() {
+        let value = Arc::new(AtomicSizeAndAmount::new());
+
+        let writers: Vec<_> = (0..2)
+            .map(|_| {
+                let value = value.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..10_000 {
+                        value.added(1);
+                        value.removed(1);
+                    }
+                })
+            })
+            .collect();
+
+        for _ in 0..10_000 {
+            let snapshot = value.get_snapshot();
+            assert_eq!(snapshot.size, snapshot.amount);
+        }
+
+        for writer in writers {
+            writer.join().unwrap();
+        }
+
+        assert_eq!(SizeAndAmount::new(), value.get_snapshot());
+    }
+}
@@ -1,4 +1,14 @@
+mod atomic_size_and_amount;
 mod size_and_amount;
+mod split_queue_with_intervals_by_sub_pages;
+mod sub_page_container;
 mod sub_page_id;
+mod sub_page_id_name;
+mod sub_page_id_range;
+pub use atomic_size_and_amount::*;
 pub use size_and_amount::*;
+pub use split_queue_with_intervals_by_sub_pages::*;
+pub use sub_page_container::*;
 pub use sub_page_id::*;
+pub use sub_page_id_name::*;
+pub use sub_page_id_range::*;
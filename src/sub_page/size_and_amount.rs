@@ -1,4 +1,5 @@
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SizeAndAmount {
     pub size: usize,
     pub amount: usize,
@@ -28,4 +29,188 @@ impl SizeAndAmount {
         self.size -= other.size;
         self.amount -= other.amount;
     }
+
+    // Value stays untouched and false is returned if accounting would go below zero
+    pub fn checked_removed(&mut self, size: usize) -> bool {
+        self.checked_removed_page(&SizeAndAmount { size, amount: 1 })
+    }
+
+    pub fn checked_removed_page(&mut self, other: &SizeAndAmount) -> bool {
+        match self.checked_sub(other) {
+            Some(result) => {
+                *self = result;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn saturating_removed(&mut self, size: usize) {
+        self.saturating_removed_page(&SizeAndAmount { size, amount: 1 });
+    }
+
+    pub fn saturating_removed_page(&mut self, other: &SizeAndAmount) {
+        *self = self.saturating_sub(other);
+    }
+
+    pub fn checked_add(&self, other: &SizeAndAmount) -> Option<Self> {
+        Some(Self {
+            size: self.size.checked_add(other.size)?,
+            amount: self.amount.checked_add(other.amount)?,
+        })
+    }
+
+    pub fn checked_sub(&self, other: &SizeAndAmount) -> Option<Self> {
+        Some(Self {
+            size: self.size.checked_sub(other.size)?,
+            amount: self.amount.checked_sub(other.amount)?,
+        })
+    }
+
+    pub fn saturating_add(&self, other: &SizeAndAmount) -> Self {
+        Self {
+            size: self.size.saturating_add(other.size),
+            amount: self.amount.saturating_add(other.amount),
+        }
+    }
+
+    pub fn saturating_sub(&self, other: &SizeAndAmount) -> Self {
+        Self {
+            size: self.size.saturating_sub(other.size),
+            amount: self.amount.saturating_sub(other.amount),
+        }
+    }
+}
+
+// Operators panic on overflow and underflow in release builds as well, since wrapped accounting
+// is never right. Use checked or saturating arithmetic if the values can go out of range
+impl std::ops::Add for SizeAndAmount {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.checked_add(&rhs)
+            .expect("SizeAndAmount addition overflow")
+    }
+}
+
+impl std::ops::Sub for SizeAndAmount {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.checked_sub(&rhs)
+            .expect("SizeAndAmount subtraction underflow")
+    }
+}
+
+impl std::ops::AddAssign for SizeAndAmount {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl std::iter::Sum for SizeAndAmount {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::new(), |acc, itm| acc + itm)
+    }
+}
+
+impl<'s> std::iter::Sum<&'s SizeAndAmount> for SizeAndAmount {
+    fn sum<I: Iterator<Item = &'s SizeAndAmount>>(iter: I) -> Self {
+        iter.fold(Self::new(), |acc, itm| acc + *itm)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create(size: usize, amount: usize) -> SizeAndAmount {
+        SizeAndAmount { size, amount }
+    }
+
+    #[test]
+    fn test_checked_removed() {
+        let mut value = create(10, 1);
+
+        assert!(!value.checked_removed(11));
+        assert_eq!(create(10, 1), value);
+
+        assert!(value.checked_removed(10));
+        assert_eq!(create(0, 0), value);
+
+        assert!(!value.checked_removed(0));
+        assert_eq!(create(0, 0), value);
+
+        let mut value = create(10, 2);
+        assert!(!value.checked_removed_page(&create(5, 3)));
+        assert!(value.checked_removed_page(&create(5, 2)));
+        assert_eq!(create(5, 0), value);
+    }
+
+    #[test]
+    fn test_saturating_removed() {
+        let mut value = create(10, 1);
+
+        value.saturating_removed(15);
+        assert_eq!(create(0, 0), value);
+
+        value.saturating_removed(1);
+        assert_eq!(create(0, 0), value);
+
+        let mut value = create(10, 5);
+        value.saturating_removed_page(&create(3, 7));
+        assert_eq!(create(7, 0), value);
+    }
+
+    #[test]
+    fn test_checked_and_saturating_arithmetic() {
+        assert_eq!(None, create(usize::MAX, 0).checked_add(&create(1, 0)));
+        assert_eq!(Some(create(3, 3)), create(1, 2).checked_add(&create(2, 1)));
+
+        assert_eq!(None, create(1, 1).checked_sub(&create(0, 2)));
+        assert_eq!(
+            create(usize::MAX, 1),
+            create(usize::MAX, 0).saturating_add(&create(1, 1))
+        );
+        assert_eq!(create(0, 1), create(1, 2).saturating_sub(&create(5, 1)));
+    }
+
+    #[test]
+    fn test_ops() {
+        assert_eq!(create(3, 3), create(1, 2) + create(2, 1));
+        assert_eq!(create(1, 1), create(3, 3) - create(2, 2));
+
+        let mut value = create(1, 1);
+        value += create(2, 3);
+        assert_eq!(create(3, 4), value);
+
+        let items = vec![create(1, 1), create(2, 2), create(3, 3)];
+
+        assert_eq!(create(6, 6), items.iter().sum());
+        assert_eq!(create(6, 6), items.into_iter().sum());
+    }
+
+    #[test]
+    #[should_panic(expected = "SizeAndAmount subtraction underflow")]
+    fn test_sub_underflow_panics() {
+        let _ = create(1, 1) - create(2, 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "SizeAndAmount addition overflow")]
+    fn test_add_assign_overflow_panics() {
+        let mut value = create(usize::MAX, 1);
+        value += create(1, 1);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde() {
+        let json = serde_json::to_string(&create(10, 1)).unwrap();
+
+        assert_eq!("{\"size\":10,\"amount\":1}", json);
+
+        let result: SizeAndAmount = serde_json::from_str(json.as_str()).unwrap();
+        assert_eq!(create(10, 1), result);
+    }
 }
@@ -0,0 +1,88 @@
+use my_service_bus_abstractions::MessageId;
+
+use crate::page_id::{MessagesBucketId, PageLayout, SplitQueueWithIntervalsIterator};
+
+use super::SubPageId;
+
+pub type SplittedBySubPageIdIterator = SplitQueueWithIntervalsIterator<SubPageId>;
+
+impl MessagesBucketId for SubPageId {
+    fn new(value: i64) -> Self {
+        SubPageId::new(value)
+    }
+
+    fn get_value(&self) -> i64 {
+        SubPageId::get_value(self)
+    }
+
+    fn from_message_id_with_layout(message_id: MessageId, layout: PageLayout) -> Self {
+        SubPageId::from_message_id_with_layout(message_id, layout)
+    }
+
+    fn get_last_message_id_with_layout(&self, layout: PageLayout) -> MessageId {
+        SubPageId::get_last_message_id_with_layout(self, layout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus_abstractions::queue_with_intervals::{QueueIndexRange, QueueWithIntervals};
+
+    use super::*;
+
+    fn to_vec(src: &QueueWithIntervals) -> Vec<(i64, i64)> {
+        src.intervals
+            .iter()
+            .map(|itm| (itm.from_id, itm.to_id))
+            .collect()
+    }
+
+    #[test]
+    fn test_both_on_the_same_sub_page() {
+        let src = QueueWithIntervals::from_single_interval(100, 200);
+
+        let result: Vec<_> = SplittedBySubPageIdIterator::new(&src).collect();
+
+        assert_eq!(1, result.len());
+        assert_eq!(0, result[0].0.get_value());
+        assert_eq!(vec![(100, 200)], to_vec(&result[0].1));
+    }
+
+    #[test]
+    fn test_split_by_sub_pages() {
+        let mut src = QueueWithIntervals::from_single_interval(998, 1_002);
+
+        src.intervals.push(QueueIndexRange {
+            from_id: 1_500,
+            to_id: 1_600,
+        });
+
+        src.intervals.push(QueueIndexRange {
+            from_id: 5_999,
+            to_id: 6_000,
+        });
+
+        let result: Vec<_> = SplittedBySubPageIdIterator::new(&src).collect();
+
+        let sub_page_ids: Vec<i64> = result.iter().map(|itm| itm.0.get_value()).collect();
+        assert_eq!(vec![0, 1, 5, 6], sub_page_ids);
+
+        assert_eq!(vec![(998, 999)], to_vec(&result[0].1));
+        assert_eq!(vec![(1_000, 1_002), (1_500, 1_600)], to_vec(&result[1].1));
+        assert_eq!(vec![(5_999, 5_999)], to_vec(&result[2].1));
+        assert_eq!(vec![(6_000, 6_000)], to_vec(&result[3].1));
+    }
+
+    #[test]
+    fn test_interval_spanning_several_sub_pages_with_layout() {
+        let layout = PageLayout::new(1_000, 100).unwrap();
+        let src = QueueWithIntervals::from_single_interval(50, 250);
+
+        let result: Vec<_> = SplittedBySubPageIdIterator::new_with_layout(&src, layout).collect();
+
+        assert_eq!(3, result.len());
+        assert_eq!(vec![(50, 99)], to_vec(&result[0].1));
+        assert_eq!(vec![(100, 199)], to_vec(&result[1].1));
+        assert_eq!(vec![(200, 250)], to_vec(&result[2].1));
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::BTreeMap;
+
+use my_service_bus_abstractions::{
+    queue_with_intervals::{QueueIndexRange, QueueWithIntervals},
+    MessageId,
+};
+
+use crate::{page_id::PageLayout, protobuf_models::MessageProtobufModel};
+
+use super::{SizeAndAmount, SubPageId};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubPageError {
+    MessageIsNotFromSubPage {
+        sub_page_id: SubPageId,
+        message_id: i64,
+    },
+}
+
+// Messages of the sub page ordered by id. Size is the size of the message content
+pub struct SubPage {
+    sub_page_id: SubPageId,
+    layout: PageLayout,
+    messages: BTreeMap<i64, MessageProtobufModel>,
+    size_and_amount: SizeAndAmount,
+}
+
+impl SubPage {
+    pub fn new(sub_page_id: SubPageId) -> Self {
+        Self::new_with_layout(sub_page_id, PageLayout::DEFAULT)
+    }
+
+    pub fn new_with_layout(sub_page_id: SubPageId, layout: PageLayout) -> Self {
+        Self {
+            sub_page_id,
+            layout,
+            messages: BTreeMap::new(),
+            size_and_amount: SizeAndAmount::new(),
+        }
+    }
+
+    pub fn get_id(&self) -> SubPageId {
+        self.sub_page_id
+    }
+
+    pub fn get_size_and_amount(&self) -> &SizeAndAmount {
+        &self.size_and_amount
+    }
+
+    // Returns replaced message if message with the same id was already there
+    pub fn insert(
+        &mut self,
+        message: MessageProtobufModel,
+    ) -> Result<Option<MessageProtobufModel>, SubPageError> {
+        let message_id = message.get_message_id();
+
+        if !self
+            .sub_page_id
+            .is_my_message_id_with_layout(message_id, self.layout)
+        {
+            return Err(SubPageError::MessageIsNotFromSubPage {
+                sub_page_id: self.sub_page_id,
+                message_id: message_id.get_value(),
+            });
+        }
+
+        self.size_and_amount.added(message.data.len());
+
+        let removed = self.messages.insert(message_id.get_value(), message);
+
+        if let Some(removed) = &removed {
+            self.size_and_amount.removed(removed.data.len());
+        }
+
+        Ok(removed)
+    }
+
+    pub fn remove(&mut self, message_id: MessageId) -> Option<MessageProtobufModel> {
+        let removed = self.messages.remove(&message_id.get_value())?;
+        self.size_and_amount.removed(removed.data.len());
+        Some(removed)
+    }
+
+    pub fn get(&self, message_id: MessageId) -> Option<&MessageProtobufModel> {
+        self.messages.get(&message_id.get_value())
+    }
+
+    pub fn has_message(&self, message_id: MessageId) -> bool {
+        self.messages.contains_key(&message_id.get_value())
+    }
+
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.messages.len() as i64 == self.layout.get_messages_in_sub_page()
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &MessageProtobufModel> {
+        self.messages.values()
+    }
+
+    pub fn get_missing_ids(&self) -> QueueWithIntervals {
+        let mut result = QueueWithIntervals::new();
+
+        let mut from_id = self
+            .sub_page_id
+            .get_first_message_id_with_layout(self.layout)
+            .get_value();
+
+        let last_id = self
+            .sub_page_id
+            .get_last_message_id_with_layout(self.layout)
+            .get_value();
+
+        for message_id in self.messages.keys() {
+            if *message_id > from_id {
+                result.intervals.push(QueueIndexRange {
+                    from_id,
+                    to_id: message_id - 1,
+                });
+            }
+
+            from_id = message_id + 1;
+        }
+
+        if from_id <= last_id {
+            result.intervals.push(QueueIndexRange {
+                from_id,
+                to_id: last_id,
+            });
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rust_extensions::date_time::DateTimeAsMicroseconds;
+
+    use super::*;
+
+    fn create_message(message_id: i64, size: usize) -> MessageProtobufModel {
+        MessageProtobufModel::new(
+            message_id.into(),
+            DateTimeAsMicroseconds::new(0),
+            vec![0; size],
+            vec![],
+        )
+    }
+
+    fn to_intervals(src: &QueueWithIntervals) -> Vec<(i64, i64)> {
+        src.intervals
+            .iter()
+            .map(|itm| (itm.from_id, itm.to_id))
+            .collect()
+    }
+
+    #[test]
+    fn test_size_and_amount_are_in_sync() {
+        let mut sub_page = SubPage::new(SubPageId::new(1));
+
+        assert!(sub_page
+            .insert(create_message(1_000, 10))
+            .unwrap()
+            .is_none());
+        assert!(sub_page
+            .insert(create_message(1_005, 20))
+            .unwrap()
+            .is_none());
+
+        assert_eq!(30, sub_page.get_size_and_amount().size);
+        assert_eq!(2, sub_page.get_size_and_amount().amount);
+
+        let replaced = sub_page.insert(create_message(1_000, 5)).unwrap().unwrap();
+        assert_eq!(10, replaced.data.len());
+
+        assert_eq!(25, sub_page.get_size_and_amount().size);
+        assert_eq!(2, sub_page.get_size_and_amount().amount);
+
+        assert_eq!(20, sub_page.remove(1_005.into()).unwrap().data.len());
+        assert!(sub_page.remove(1_005.into()).is_none());
+
+        assert_eq!(5, sub_page.get_size_and_amount().size);
+        assert_eq!(1, sub_page.get_size_and_amount().amount);
+    }
+
+    #[test]
+    fn test_message_from_other_sub_page_is_rejected() {
+        let mut sub_page = SubPage::new(SubPageId::new(1));
+
+        assert_eq!(
+            Err(SubPageError::MessageIsNotFromSubPage {
+                sub_page_id: SubPageId::new(1),
+                message_id: 2_000
+            }),
+            sub_page.insert(create_message(2_000, 10)).map(|_| ())
+        );
+
+        assert!(sub_page.is_empty());
+        assert_eq!(0, sub_page.get_size_and_amount().amount);
+    }
+
+    #[test]
+    fn test_messages_are_ordered() {
+        let mut sub_page = SubPage::new(SubPageId::new(0));
+
+        for message_id in [5, 1, 3] {
+            sub_page.insert(create_message(message_id, 1)).unwrap();
+        }
+
+        let result: Vec<i64> = sub_page
+            .iter()
+            .map(|itm| itm.get_message_id().get_value())
+            .collect();
+
+        assert_eq!(vec![1, 3, 5], result);
+        assert!(sub_page.has_message(3.into()));
+        assert!(sub_page.get(4.into()).is_none());
+    }
+
+    #[test]
+    fn test_missing_ids() {
+        let layout = PageLayout::new(100, 10).unwrap();
+        let mut sub_page = SubPage::new_with_layout(SubPageId::new(2), layout);
+
+        assert_eq!(vec![(20, 29)], to_intervals(&sub_page.get_missing_ids()));
+
+        for message_id in [20, 21, 25, 29] {
+            sub_page.insert(create_message(message_id, 1)).unwrap();
+        }
+
+        assert_eq!(
+            vec![(22, 24), (26, 28)],
+            to_intervals(&sub_page.get_missing_ids())
+        );
+
+        for message_id in 20..30 {
+            sub_page.insert(create_message(message_id, 1)).unwrap();
+        }
+
+        assert!(sub_page.is_full());
+        assert!(sub_page.get_missing_ids().intervals.is_empty());
+    }
+}
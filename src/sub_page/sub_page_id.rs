@@ -1,52 +1,236 @@
 use my_service_bus_abstractions::MessageId;
 
-use crate::page_id::PageId;
+use crate::page_id::{PageId, PageLayout};
 
 pub const SUB_PAGE_MESSAGES_AMOUNT: i64 = 1000;
 pub const SUB_PAGES_PER_PAGE: i64 = 100;
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidSubPageId {
+    NegativeMessageId(i64),
+    NegativeSubPageId(i64),
+    SubPageIdIsOutOfRange {
+        sub_page_id: i64,
+        max_sub_page_id: i64,
+    },
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(transparent))]
 pub struct SubPageId(i64);
 
 impl SubPageId {
     pub fn new(value: i64) -> Self {
         Self(value)
     }
+
+    pub fn try_new(value: i64) -> Result<Self, InvalidSubPageId> {
+        Self::try_new_with_layout(value, PageLayout::DEFAULT)
+    }
+
+    pub fn try_new_with_layout(value: i64, layout: PageLayout) -> Result<Self, InvalidSubPageId> {
+        if value < 0 {
+            return Err(InvalidSubPageId::NegativeSubPageId(value));
+        }
+
+        let max_sub_page_id = layout.get_max_sub_page_id();
+
+        if value > max_sub_page_id {
+            return Err(InvalidSubPageId::SubPageIdIsOutOfRange {
+                sub_page_id: value,
+                max_sub_page_id,
+            });
+        }
+
+        Ok(Self(value))
+    }
+
+    // Negative message ids are rounded down the same way as PageId does
     pub fn from_message_id(message_id: MessageId) -> Self {
-        Self(message_id.get_value() / SUB_PAGE_MESSAGES_AMOUNT)
+        Self::from_message_id_with_layout(message_id, PageLayout::DEFAULT)
+    }
+
+    pub fn from_message_id_with_layout(message_id: MessageId, layout: PageLayout) -> Self {
+        Self(
+            message_id
+                .get_value()
+                .div_euclid(layout.get_messages_in_sub_page()),
+        )
+    }
+
+    pub fn try_from_message_id(message_id: MessageId) -> Result<Self, InvalidSubPageId> {
+        Self::try_from_message_id_with_layout(message_id, PageLayout::DEFAULT)
+    }
+
+    pub fn try_from_message_id_with_layout(
+        message_id: MessageId,
+        layout: PageLayout,
+    ) -> Result<Self, InvalidSubPageId> {
+        if message_id.get_value() < 0 {
+            return Err(InvalidSubPageId::NegativeMessageId(message_id.get_value()));
+        }
+
+        Ok(Self::from_message_id_with_layout(message_id, layout))
     }
 
     pub fn from_page_id(page_id: PageId) -> Self {
-        Self(page_id.get_value() * SUB_PAGES_PER_PAGE)
+        Self::from_page_id_with_layout(page_id, PageLayout::DEFAULT)
+    }
+
+    // Panics if the first sub page of the page does not fit into i64
+    pub fn from_page_id_with_layout(page_id: PageId, layout: PageLayout) -> Self {
+        Self(
+            page_id
+                .get_value()
+                .checked_mul(layout.get_sub_pages_per_page())
+                .expect("Page id is out of range of the layout"),
+        )
     }
 
     pub fn get_value(&self) -> i64 {
         self.0
     }
 
+    pub fn get_page_id_with_layout(&self, layout: PageLayout) -> PageId {
+        PageId::from_sub_page_id_with_layout(*self, layout)
+    }
+
     pub fn get_first_message_id(&self) -> MessageId {
-        let result = self.get_value() * SUB_PAGE_MESSAGES_AMOUNT;
-        result.into()
+        self.get_first_message_id_with_layout(PageLayout::DEFAULT)
+    }
+
+    // Message id getters panic if the id does not fit into i64, which is never the case
+    // for sub page ids within 0..=max_sub_page_id. Checked versions return None instead
+    pub fn get_first_message_id_with_layout(&self, layout: PageLayout) -> MessageId {
+        self.checked_get_first_message_id_with_layout(layout)
+            .expect("Sub page id is out of range of the layout")
+    }
+
+    pub fn checked_get_first_message_id(&self) -> Option<MessageId> {
+        self.checked_get_first_message_id_with_layout(PageLayout::DEFAULT)
+    }
+
+    pub fn checked_get_first_message_id_with_layout(
+        &self,
+        layout: PageLayout,
+    ) -> Option<MessageId> {
+        let result = self.0.checked_mul(layout.get_messages_in_sub_page())?;
+        Some(result.into())
     }
 
     pub fn get_last_message_id(&self) -> MessageId {
-        let result = self.get_first_message_id_of_next_sub_page().get_value() - 1;
-        result.into()
+        self.get_last_message_id_with_layout(PageLayout::DEFAULT)
+    }
+
+    pub fn get_last_message_id_with_layout(&self, layout: PageLayout) -> MessageId {
+        self.checked_get_last_message_id_with_layout(layout)
+            .expect("Sub page id is out of range of the layout")
+    }
+
+    pub fn checked_get_last_message_id(&self) -> Option<MessageId> {
+        self.checked_get_last_message_id_with_layout(PageLayout::DEFAULT)
+    }
+
+    pub fn checked_get_last_message_id_with_layout(&self, layout: PageLayout) -> Option<MessageId> {
+        let result = self
+            .0
+            .checked_mul(layout.get_messages_in_sub_page())?
+            .checked_add(layout.get_messages_in_sub_page() - 1)?;
+
+        Some(result.into())
+    }
+
+    // Checked and saturating arithmetic keeps sub page id within 0..=max_sub_page_id of the layout
+    pub fn checked_add(&self, rhs: i64) -> Option<Self> {
+        self.checked_add_with_layout(rhs, PageLayout::DEFAULT)
+    }
+
+    pub fn checked_add_with_layout(&self, rhs: i64, layout: PageLayout) -> Option<Self> {
+        Self::try_new_with_layout(self.0.checked_add(rhs)?, layout).ok()
+    }
+
+    pub fn checked_sub(&self, rhs: i64) -> Option<Self> {
+        self.checked_sub_with_layout(rhs, PageLayout::DEFAULT)
+    }
+
+    pub fn checked_sub_with_layout(&self, rhs: i64, layout: PageLayout) -> Option<Self> {
+        Self::try_new_with_layout(self.0.checked_sub(rhs)?, layout).ok()
+    }
+
+    pub fn saturating_add(&self, rhs: i64) -> Self {
+        self.saturating_add_with_layout(rhs, PageLayout::DEFAULT)
+    }
+
+    pub fn saturating_add_with_layout(&self, rhs: i64, layout: PageLayout) -> Self {
+        Self(
+            self.0
+                .saturating_add(rhs)
+                .clamp(0, layout.get_max_sub_page_id()),
+        )
+    }
+
+    pub fn saturating_sub(&self, rhs: i64) -> Self {
+        self.saturating_sub_with_layout(rhs, PageLayout::DEFAULT)
+    }
+
+    pub fn saturating_sub_with_layout(&self, rhs: i64, layout: PageLayout) -> Self {
+        Self(
+            self.0
+                .saturating_sub(rhs)
+                .clamp(0, layout.get_max_sub_page_id()),
+        )
     }
 
     pub fn get_first_message_id_of_next_sub_page(&self) -> MessageId {
-        let result = self.get_first_message_id().get_value() + SUB_PAGE_MESSAGES_AMOUNT;
-        result.into()
+        self.get_first_message_id_of_next_sub_page_with_layout(PageLayout::DEFAULT)
+    }
+
+    // Panics for the sub page which ends at i64::MAX - there is no next one
+    pub fn get_first_message_id_of_next_sub_page_with_layout(
+        &self,
+        layout: PageLayout,
+    ) -> MessageId {
+        self.checked_get_first_message_id_of_next_sub_page_with_layout(layout)
+            .expect("Next sub page is out of range of the layout")
+    }
+
+    pub fn checked_get_first_message_id_of_next_sub_page(&self) -> Option<MessageId> {
+        self.checked_get_first_message_id_of_next_sub_page_with_layout(PageLayout::DEFAULT)
+    }
+
+    pub fn checked_get_first_message_id_of_next_sub_page_with_layout(
+        &self,
+        layout: PageLayout,
+    ) -> Option<MessageId> {
+        let result = self
+            .checked_get_last_message_id_with_layout(layout)?
+            .get_value()
+            .checked_add(1)?;
+
+        Some(result.into())
     }
 
-    pub fn iterate_message_ids(&self) -> std::ops::Range<i64> {
-        let first_message_id = self.get_first_message_id().get_value();
-        first_message_id..first_message_id + SUB_PAGE_MESSAGES_AMOUNT
+    // Range is inclusive, so the last sub page of a layout may end at i64::MAX
+    pub fn iterate_message_ids(&self) -> std::ops::RangeInclusive<i64> {
+        self.iterate_message_ids_with_layout(PageLayout::DEFAULT)
+    }
+
+    pub fn iterate_message_ids_with_layout(
+        &self,
+        layout: PageLayout,
+    ) -> std::ops::RangeInclusive<i64> {
+        self.get_first_message_id_with_layout(layout).get_value()
+            ..=self.get_last_message_id_with_layout(layout).get_value()
     }
 
     pub fn is_my_message_id(&self, message_id: MessageId) -> bool {
-        let first_message_id = self.get_first_message_id().get_value();
-        let last_message_id = self.get_last_message_id().get_value();
+        self.is_my_message_id_with_layout(message_id, PageLayout::DEFAULT)
+    }
+
+    pub fn is_my_message_id_with_layout(&self, message_id: MessageId, layout: PageLayout) -> bool {
+        let first_message_id = self.get_first_message_id_with_layout(layout).get_value();
+        let last_message_id = self.get_last_message_id_with_layout(layout).get_value();
 
         let message_id = message_id.get_value();
 
@@ -141,7 +325,8 @@ mod test {
 
 #[cfg(test)]
 mod tests {
-    use super::SubPageId;
+    use super::{InvalidSubPageId, SubPageId};
+    use crate::page_id::{PageId, PageLayout};
 
     #[test]
     fn test_b_tree_map() {
@@ -210,4 +395,180 @@ mod tests {
             sub_page.get_first_message_id_of_next_sub_page().get_value()
         );
     }
+
+    #[test]
+    fn test_custom_layout() {
+        let layout = PageLayout::new(1_000, 100).unwrap();
+
+        let sub_page = SubPageId::from_message_id_with_layout(2_550.into(), layout);
+        assert_eq!(25, sub_page.get_value());
+
+        assert_eq!(
+            2_500,
+            sub_page
+                .get_first_message_id_with_layout(layout)
+                .get_value()
+        );
+        assert_eq!(
+            2_599,
+            sub_page.get_last_message_id_with_layout(layout).get_value()
+        );
+        assert_eq!(
+            2_500..=2_599,
+            sub_page.iterate_message_ids_with_layout(layout)
+        );
+
+        assert!(sub_page.is_my_message_id_with_layout(2_599.into(), layout));
+        assert!(!sub_page.is_my_message_id_with_layout(2_600.into(), layout));
+
+        assert_eq!(2, sub_page.get_page_id_with_layout(layout).get_value());
+        assert_eq!(
+            20,
+            SubPageId::from_page_id_with_layout(PageId::new(2), layout).get_value()
+        );
+    }
+
+    #[test]
+    fn test_negative_message_ids() {
+        assert_eq!(-1, SubPageId::from_message_id((-1).into()).get_value());
+        assert_eq!(-2, SubPageId::from_message_id((-1_001).into()).get_value());
+
+        assert_eq!(
+            Err(InvalidSubPageId::NegativeMessageId(-1)),
+            SubPageId::try_from_message_id((-1).into())
+        );
+    }
+
+    #[test]
+    fn test_try_new_and_checked_arithmetic() {
+        let max_sub_page_id = PageLayout::DEFAULT.get_max_sub_page_id();
+
+        assert_eq!(
+            Err(InvalidSubPageId::NegativeSubPageId(-1)),
+            SubPageId::try_new(-1)
+        );
+        assert_eq!(
+            Err(InvalidSubPageId::SubPageIdIsOutOfRange {
+                sub_page_id: max_sub_page_id + 1,
+                max_sub_page_id
+            }),
+            SubPageId::try_new(max_sub_page_id + 1)
+        );
+
+        let last = SubPageId::try_new(max_sub_page_id).unwrap();
+        assert!(last.checked_get_last_message_id().is_some());
+        assert_eq!(None, last.checked_add(1));
+        assert_eq!(last, last.saturating_add(10));
+
+        assert_eq!(None, SubPageId::new(0).checked_sub(1));
+        assert_eq!(SubPageId::new(0), SubPageId::new(3).saturating_sub(10));
+        assert_eq!(Some(SubPageId::new(2)), SubPageId::new(3).checked_sub(1));
+    }
+
+    #[test]
+    fn test_max_sub_page_belongs_to_max_page() {
+        for layout in [
+            PageLayout::DEFAULT,
+            PageLayout::new(1_000, 100).unwrap(),
+            PageLayout::new(1024, 1).unwrap(),
+            PageLayout::new(1, 1).unwrap(),
+        ] {
+            let max_sub_page_id =
+                SubPageId::try_new_with_layout(layout.get_max_sub_page_id(), layout).unwrap();
+
+            assert_eq!(
+                layout.get_max_page_id(),
+                max_sub_page_id.get_page_id_with_layout(layout).get_value()
+            );
+
+            let max_page_id = PageId::new(layout.get_max_page_id());
+            assert_eq!(
+                max_page_id.get_last_message_id_with_layout(layout),
+                max_sub_page_id.get_last_message_id_with_layout(layout)
+            );
+            assert_eq!(
+                Some(max_sub_page_id),
+                max_page_id
+                    .iterate_sub_page_ids_with_layout(layout)
+                    .next_back()
+            );
+        }
+
+        let max_sub_page_id = PageLayout::DEFAULT.get_max_sub_page_id();
+        assert_eq!(9_223_372_036_854_699, max_sub_page_id);
+        assert!(SubPageId::try_new(max_sub_page_id + 1).is_err());
+    }
+
+    #[test]
+    fn test_message_ids_of_out_of_range_sub_pages() {
+        let layout = PageLayout::new(1024, 1).unwrap();
+        let last = SubPageId::new(layout.get_max_sub_page_id());
+
+        assert_eq!(
+            i64::MAX,
+            last.get_last_message_id_with_layout(layout).get_value()
+        );
+        assert_eq!(
+            i64::MAX..=i64::MAX,
+            last.iterate_message_ids_with_layout(layout)
+        );
+        assert_eq!(
+            None,
+            last.checked_get_first_message_id_of_next_sub_page_with_layout(layout)
+        );
+
+        let above_max = SubPageId::new(i64::MAX / 1_000 + 1);
+        assert_eq!(None, above_max.checked_get_first_message_id());
+        assert_eq!(None, above_max.checked_get_last_message_id());
+        assert_eq!(
+            None,
+            above_max.checked_get_first_message_id_of_next_sub_page()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_last_message_id_overflow_panics() {
+        SubPageId::new(i64::MAX / 1_000 + 1).get_last_message_id();
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_sub_page_of_out_of_range_page_panics() {
+        SubPageId::from_page_id(PageId::new(i64::MAX / 100 + 1));
+    }
+
+    #[test]
+    fn test_checked_and_saturating_arithmetic_with_layout() {
+        let layout = PageLayout::new(1_000, 100).unwrap();
+        let max_sub_page_id = layout.get_max_sub_page_id();
+        let last = SubPageId::new(max_sub_page_id);
+
+        assert!(max_sub_page_id > PageLayout::DEFAULT.get_max_sub_page_id());
+
+        assert_eq!(
+            Some(last),
+            SubPageId::new(max_sub_page_id - 1).checked_add_with_layout(1, layout)
+        );
+        assert_eq!(None, last.checked_add_with_layout(1, layout));
+        assert_eq!(None, SubPageId::new(0).checked_sub_with_layout(1, layout));
+
+        assert_eq!(last, last.saturating_add_with_layout(10, layout));
+        assert_eq!(
+            SubPageId::new(0),
+            SubPageId::new(3).saturating_sub_with_layout(10, layout)
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_as_plain_number() {
+        assert_eq!(
+            "1500",
+            serde_json::to_string(&SubPageId::new(1500)).unwrap()
+        );
+
+        let sub_page_id: SubPageId = serde_json::from_str("1500").unwrap();
+        assert_eq!(1500, sub_page_id.get_value());
+    }
 }
@@ -0,0 +1,108 @@
+use std::str::FromStr;
+
+use crate::page_id::{format_canonical_id, parse_canonical_id, split_file_name, InvalidIdName};
+
+use super::SubPageId;
+
+pub const SUB_PAGE_FILE_EXTENSION: &str = "subpage";
+
+impl SubPageId {
+    // Same as for PageId, only 0..=max_sub_page_id of the default layout is parsed back
+    pub fn to_canonical_string(&self) -> String {
+        format_canonical_id(self.get_value())
+    }
+
+    pub fn to_file_name(&self) -> String {
+        format!("{}.{}", self.to_canonical_string(), SUB_PAGE_FILE_EXTENSION)
+    }
+
+    pub fn from_file_name(src: &str) -> Result<Self, InvalidIdName> {
+        split_file_name(src, SUB_PAGE_FILE_EXTENSION)?.parse()
+    }
+}
+
+impl FromStr for SubPageId {
+    type Err = InvalidIdName;
+
+    fn from_str(src: &str) -> Result<Self, Self::Err> {
+        let value = parse_canonical_id(src)?;
+        SubPageId::try_new(value).map_err(|_| InvalidIdName::IsOutOfRange(src.to_string()))
+    }
+}
+
+impl<'s> TryFrom<&'s str> for SubPageId {
+    type Error = InvalidIdName;
+
+    fn try_from(src: &'s str) -> Result<Self, Self::Error> {
+        src.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::page_id::PageLayout;
+
+    use super::*;
+
+    #[test]
+    fn test_canonical_string() {
+        let sub_page_id = SubPageId::new(12_345);
+
+        assert_eq!("0000000000000012345", sub_page_id.to_canonical_string());
+        assert_eq!("0000000000000012345.subpage", sub_page_id.to_file_name());
+
+        assert_eq!(
+            sub_page_id,
+            SubPageId::from_file_name(&sub_page_id.to_file_name()).unwrap()
+        );
+        assert_eq!(
+            sub_page_id,
+            SubPageId::try_from("0000000000000012345").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_page_file_is_not_a_sub_page_file() {
+        assert_eq!(
+            Err(InvalidIdName::WrongExtension {
+                expected: SUB_PAGE_FILE_EXTENSION,
+                actual: "page".to_string()
+            }),
+            SubPageId::from_file_name("0000000000000012345.page")
+        );
+    }
+
+    #[test]
+    fn test_sub_page_id_is_out_of_range() {
+        assert!(matches!(
+            "9223372036854775000".parse::<SubPageId>(),
+            Err(InvalidIdName::IsOutOfRange(_))
+        ));
+    }
+
+    #[test]
+    fn test_round_trip_at_the_edges_of_the_range() {
+        let max_sub_page_id = PageLayout::DEFAULT.get_max_sub_page_id();
+
+        for id in [0, max_sub_page_id] {
+            let sub_page_id = SubPageId::new(id);
+            assert_eq!(
+                Ok(sub_page_id),
+                SubPageId::from_file_name(&sub_page_id.to_file_name())
+            );
+        }
+
+        let above_max = SubPageId::new(max_sub_page_id + 1).to_canonical_string();
+        assert_eq!(
+            Err(InvalidIdName::IsOutOfRange(above_max.clone())),
+            above_max.parse::<SubPageId>()
+        );
+
+        assert!(matches!(
+            SubPageId::new(-1)
+                .to_canonical_string()
+                .parse::<SubPageId>(),
+            Err(InvalidIdName::NotADigit { position: 0, .. })
+        ));
+    }
+}
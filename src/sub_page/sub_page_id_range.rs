@@ -0,0 +1,94 @@
+use crate::page_id::{
+    MessagesBucketIdRange, MessagesBucketIdRangeIterator, PageIdRange, PageLayout,
+};
+
+use super::SubPageId;
+
+pub type SubPageIdRange = MessagesBucketIdRange<SubPageId>;
+
+pub type SubPageIdRangeIterator = MessagesBucketIdRangeIterator<SubPageId>;
+
+impl MessagesBucketIdRange<SubPageId> {
+    pub fn get_page_id_range(&self) -> PageIdRange {
+        self.get_page_id_range_with_layout(PageLayout::DEFAULT)
+    }
+
+    pub fn get_page_id_range_with_layout(&self, layout: PageLayout) -> PageIdRange {
+        if self.is_empty() {
+            return PageIdRange::empty();
+        }
+
+        PageIdRange::new(
+            self.get_from().get_page_id_with_layout(layout),
+            self.get_to().get_page_id_with_layout(layout),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use my_service_bus_abstractions::queue_with_intervals::QueueWithIntervals;
+
+    use super::*;
+
+    #[test]
+    fn test_from_message_ids() {
+        let result = SubPageIdRange::from_message_ids(999.into(), 3_000.into());
+
+        assert_eq!(SubPageIdRange::from_values(0, 3), result);
+        assert_eq!(4, result.len());
+        assert!(result.contains(SubPageId::new(3)));
+        assert!(!result.contains(SubPageId::new(4)));
+    }
+
+    #[test]
+    fn test_from_queue_with_intervals() {
+        let src = QueueWithIntervals::from_single_interval(1_500, 2_500);
+        assert_eq!(
+            SubPageIdRange::from_values(1, 2),
+            SubPageIdRange::from_queue_with_intervals(&src)
+        );
+
+        assert!(SubPageIdRange::from_queue_with_intervals(&QueueWithIntervals::new()).is_empty());
+    }
+
+    #[test]
+    fn test_set_operations() {
+        assert_eq!(
+            SubPageIdRange::from_values(3, 5),
+            SubPageIdRange::from_values(1, 5).intersect(&SubPageIdRange::from_values(3, 8))
+        );
+        assert!(SubPageIdRange::from_values(1, 2)
+            .intersect(&SubPageIdRange::from_values(3, 4))
+            .is_empty());
+
+        assert_eq!(
+            Some(SubPageIdRange::from_values(1, 4)),
+            SubPageIdRange::from_values(1, 2).union(&SubPageIdRange::from_values(3, 4))
+        );
+        assert_eq!(
+            None,
+            SubPageIdRange::from_values(1, 2).union(&SubPageIdRange::from_values(4, 5))
+        );
+    }
+
+    #[test]
+    fn test_iteration_in_both_directions() {
+        let result: Vec<i64> = SubPageIdRange::from_values(5, 7)
+            .iter()
+            .rev()
+            .map(|itm| itm.get_value())
+            .collect();
+        assert_eq!(vec![7, 6, 5], result);
+
+        assert_eq!(3, SubPageIdRange::from_values(5, 7).into_iter().count());
+    }
+
+    #[test]
+    fn test_page_id_range() {
+        let result = SubPageIdRange::from_values(99, 201).get_page_id_range();
+
+        assert_eq!(0, result.get_from().get_value());
+        assert_eq!(2, result.get_to().get_value());
+    }
+}